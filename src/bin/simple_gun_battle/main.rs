@@ -1,45 +1,42 @@
-mod framepace;
-mod game;
-mod start_menu;
-mod ui_utils;
-
-use avian3d::prelude::*;
-use bevy::{input::common_conditions::input_just_pressed, prelude::*, window::WindowMode};
-use framepace::FramepacePlugin;
+use bevy::prelude::*;
+use bevy_games_0_15::simple_gun_battle::{GunBattleConfig, GunBattlePlugin};
 
 fn main() -> AppExit {
-    App::new()
-        .add_plugins(
-            DefaultPlugins
-                .set(AssetPlugin {
-                    file_path: "assets/simple_gun_battle".into(),
-                    ..default()
-                })
-                .set(WindowPlugin {
-                    primary_window: Some(Window {
-                        title: "简单枪战".into(),
-                        mode: WindowMode::BorderlessFullscreen(MonitorSelection::Primary),
-                        ..default()
-                    }),
-                    ..default()
-                }),
-        )
-        .add_plugins((PhysicsPlugins::default(), FramepacePlugin))
-        .add_plugins((ui_utils::plugin, start_menu::plugin, game::plugin))
-        .init_state::<AppState>()
-        .enable_state_scoped_entities::<AppState>()
-        .insert_resource(SubstepCount(12))
-        .add_systems(Update, exit_app.run_if(input_just_pressed(KeyCode::Escape)))
-        .run()
+    let mut config = GunBattleConfig {
+        headless: has_flag("--headless"),
+        record_input: arg_value("--record").map(Into::into),
+        replay_input: arg_value("--replay").map(Into::into),
+        ..default()
+    };
+    match arg_value("--seed").map(|seed| seed.parse()) {
+        Some(Ok(seed)) => config.seed = Some(seed),
+        Some(Err(error)) => eprintln!("ignoring invalid --seed value: {error}"),
+        None => {}
+    }
+    match arg_value("--level").map(|level| level.parse()) {
+        Some(Ok(level)) => config.starting_level = level,
+        Some(Err(error)) => eprintln!("ignoring invalid --level value: {error}"),
+        None => {}
+    }
+    App::new().add_plugins(GunBattlePlugin { config }).run()
 }
 
-#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Hash, States)]
-enum AppState {
-    #[default]
-    StartMenu,
-    Game,
+fn has_flag(name: &str) -> bool {
+    std::env::args().skip(1).any(|arg| arg == name)
 }
 
-fn exit_app(mut exit_writer: EventWriter<AppExit>) {
-    exit_writer.send_default();
+fn arg_value(name: &str) -> Option<String> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.strip_prefix(name) {
+            Some("") => return args.next(),
+            Some(value) => {
+                if let Some(value) = value.strip_prefix('=') {
+                    return Some(value.to_string());
+                }
+            }
+            None => {}
+        }
+    }
+    None
 }
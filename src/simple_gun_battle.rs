@@ -0,0 +1,140 @@
+mod controls;
+mod focus;
+mod framepace;
+mod game;
+mod headless;
+mod input_map;
+mod locale;
+mod save;
+mod settings;
+mod start_menu;
+mod ui_utils;
+
+use std::path::PathBuf;
+
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use framepace::FramepacePlugin;
+use game::{RecordInput, ReplayInput, RngSeed};
+use input_map::action_just_pressed;
+
+pub use focus::Focused;
+pub use game::{
+    Ammo, CharacterController, DamageEvent, DamageKind, DespawnScenePlayer, Enemy, EnemyAi,
+    EnemyCount, GameScene, GameState, HealthPoints, MovementState, NavAgent, NavMesh, Player,
+    PlayerView, Reloading, SceneIndex, Stamina, STAMINA_RECOVERED,
+};
+pub use input_map::{Action, Binding, InputBindings, WheelDirection};
+pub use locale::{Language, Localized};
+pub use save::{Progress, SAVE_FILE_VAR};
+pub use settings::{DisplayMode, FrameLimit, Settings};
+pub use ui_utils::{
+    Dropdown, DropdownChanged, Slider, SliderChanged, SpawnUi, TextInput, TextInputChanged,
+    TextInputSubmitted, Toggle, ToggleChanged, Widgets,
+};
+
+#[derive(Default)]
+pub struct GunBattlePlugin {
+    pub config: GunBattleConfig,
+}
+
+/// Startup options that are not saved. The window's display mode is a
+/// player setting and comes from [`Settings::display_mode`] instead.
+#[derive(Resource, Debug, Clone)]
+pub struct GunBattleConfig {
+    pub asset_root: String,
+    pub headless: bool,
+    pub starting_level: usize,
+    pub physics_substeps: u32,
+    pub seed: Option<u64>,
+    /// Overrides [`SAVE_FILE_VAR`] and the per-user config directory.
+    pub save_file: Option<PathBuf>,
+    pub record_input: Option<PathBuf>,
+    pub replay_input: Option<PathBuf>,
+}
+
+impl Default for GunBattleConfig {
+    fn default() -> Self {
+        Self {
+            asset_root: "assets/simple_gun_battle".into(),
+            headless: false,
+            starting_level: 0,
+            physics_substeps: 12,
+            seed: None,
+            save_file: None,
+            record_input: None,
+            replay_input: None,
+        }
+    }
+}
+
+impl Plugin for GunBattlePlugin {
+    fn build(&self, app: &mut App) {
+        let config = &self.config;
+        app.insert_resource(config.clone());
+        save::load_save_file(app);
+        if config.headless {
+            app.add_plugins(headless::plugin);
+        } else {
+            let window_mode = app
+                .world()
+                .resource::<Settings>()
+                .display_mode
+                .window_mode();
+            app.add_plugins(
+                DefaultPlugins
+                    .set(AssetPlugin {
+                        file_path: config.asset_root.clone(),
+                        ..default()
+                    })
+                    .set(WindowPlugin {
+                        primary_window: Some(Window {
+                            title: "简单枪战".into(),
+                            mode: window_mode,
+                            ..default()
+                        }),
+                        ..default()
+                    }),
+            )
+            .add_plugins(FramepacePlugin);
+        }
+        app.add_plugins(PhysicsPlugins::default())
+            .add_plugins((
+                ui_utils::plugin,
+                input_map::plugin,
+                locale::plugin,
+                focus::plugin,
+                start_menu::plugin,
+                settings::plugin,
+                controls::plugin,
+                save::plugin,
+                game::plugin,
+            ))
+            .init_state::<AppState>()
+            .enable_state_scoped_entities::<AppState>()
+            .insert_resource(SubstepCount(config.physics_substeps))
+            .add_systems(Update, exit_app.run_if(action_just_pressed(Action::Exit)));
+        if let Some(seed) = config.seed {
+            app.insert_resource(RngSeed(seed));
+        }
+        if let Some(path) = &config.record_input {
+            app.insert_resource(RecordInput(path.clone()));
+        }
+        if let Some(path) = &config.replay_input {
+            app.insert_resource(ReplayInput(path.clone()));
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Hash, States)]
+pub enum AppState {
+    #[default]
+    StartMenu,
+    Settings,
+    Controls,
+    Game,
+}
+
+fn exit_app(mut exit_writer: EventWriter<AppExit>) {
+    exit_writer.send_default();
+}
@@ -0,0 +1,215 @@
+use bevy::prelude::*;
+
+use crate::simple_gun_battle::{
+    game::GameState,
+    input_map::{Action, AwaitingBinding, Binding, InputBindings, WheelInput},
+    locale::Localized,
+    ui_utils::{Widgets, DEFAULT_FONT},
+    AppState,
+};
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(AppState::Controls), spawn_start_controls)
+        .add_systems(OnEnter(GameState::Controls), spawn_game_controls)
+        .add_systems(OnExit(AppState::Controls), cancel_rebinding)
+        .add_systems(OnExit(GameState::Controls), cancel_rebinding)
+        .add_systems(
+            Update,
+            (
+                pressed_controls_button,
+                capture_binding
+                    .never_param_warn()
+                    .run_if(resource_exists::<AwaitingBinding>),
+                update_binding_labels,
+            )
+                .run_if(in_state(AppState::Controls).or(in_state(GameState::Controls))),
+        );
+}
+
+#[derive(Component)]
+enum ControlsButton {
+    Rebind(Action),
+    Reset,
+    Back,
+}
+
+#[derive(Component)]
+struct BindingLabel(Action);
+
+#[derive(Component)]
+struct ConflictText;
+
+fn spawn_start_controls(commands: Commands) {
+    spawn_controls(commands, AppState::Controls);
+}
+
+fn spawn_game_controls(commands: Commands) {
+    spawn_controls(commands, GameState::Controls);
+}
+
+fn spawn_controls(mut commands: Commands, state_scoped: impl States) {
+    commands
+        .column(Val::Px(8.), Color::BLACK.with_alpha(0.5), state_scoped)
+        .with_children(|parent| {
+            parent.title("controls.title");
+            parent
+                .spawn(Node {
+                    display: Display::Grid,
+                    grid_template_columns: RepeatedGridTrack::auto(2),
+                    column_gap: Val::Px(48.),
+                    row_gap: Val::Px(8.),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    for action in Action::ALL {
+                        parent
+                            .spawn(Node {
+                                align_items: AlignItems::Center,
+                                column_gap: Val::Px(24.),
+                                ..default()
+                            })
+                            .with_children(|parent| {
+                                parent.spawn((
+                                    action.label(),
+                                    controls_font(),
+                                    Node {
+                                        width: Val::Px(320.),
+                                        ..default()
+                                    },
+                                ));
+                                parent
+                                    .spawn((
+                                        Button,
+                                        Node {
+                                            justify_content: JustifyContent::Center,
+                                            align_items: AlignItems::Center,
+                                            width: Val::Px(350.),
+                                            height: Val::Px(70.),
+                                            border: UiRect::all(Val::Px(6.)),
+                                            ..default()
+                                        },
+                                        BorderRadius::all(Val::Percent(50.)),
+                                        BorderColor(Color::BLACK),
+                                        ControlsButton::Rebind(action),
+                                    ))
+                                    .with_child((
+                                        Localized::default(),
+                                        controls_font(),
+                                        BindingLabel(action),
+                                    ));
+                            });
+                    }
+                });
+            parent.spawn((Localized::default(), controls_font(), ConflictText));
+            parent
+                .spawn(Node {
+                    column_gap: Val::Px(24.),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.button("controls.reset", ControlsButton::Reset);
+                    parent.button("common.back", ControlsButton::Back);
+                });
+        });
+}
+
+fn controls_font() -> TextFont {
+    TextFont {
+        font: DEFAULT_FONT,
+        font_size: 45.,
+        ..default()
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn pressed_controls_button(
+    mut commands: Commands,
+    buttons: Query<(&Interaction, &ControlsButton), Changed<Interaction>>,
+    mut bindings: ResMut<InputBindings>,
+    awaiting_binding: Option<Res<AwaitingBinding>>,
+    mut conflict_text: Single<&mut Localized, With<ConflictText>>,
+    app_state: Res<State<AppState>>,
+    mut app_next_state: ResMut<NextState<AppState>>,
+    mut game_next_state: ResMut<NextState<GameState>>,
+) {
+    if awaiting_binding.is_some() {
+        return;
+    }
+    for (interaction, controls_button) in &buttons {
+        if let Interaction::Pressed = interaction {
+            match controls_button {
+                ControlsButton::Rebind(action) => {
+                    **conflict_text = Localized::default();
+                    commands.insert_resource(AwaitingBinding(*action));
+                }
+                ControlsButton::Reset => {
+                    **conflict_text = Localized::default();
+                    bindings.reset();
+                }
+                ControlsButton::Back => {
+                    if *app_state.get() == AppState::Controls {
+                        app_next_state.set(AppState::Settings);
+                    } else {
+                        game_next_state.set(GameState::Settings);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn capture_binding(
+    mut commands: Commands,
+    awaiting_binding: Res<AwaitingBinding>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mouse_button: Res<ButtonInput<MouseButton>>,
+    wheel: Res<WheelInput>,
+    mut bindings: ResMut<InputBindings>,
+    mut conflict_text: Single<&mut Localized, With<ConflictText>>,
+) {
+    if keyboard.just_pressed(KeyCode::Escape) {
+        commands.remove_resource::<AwaitingBinding>();
+        return;
+    }
+    let binding = keyboard
+        .get_just_pressed()
+        .next()
+        .map(|key_code| Binding::Key(*key_code))
+        .or_else(|| {
+            mouse_button
+                .get_just_pressed()
+                .next()
+                .map(|mouse_button| Binding::Mouse(*mouse_button))
+        })
+        .or_else(|| wheel.0.map(Binding::Wheel));
+    let Some(binding) = binding else {
+        return;
+    };
+    commands.remove_resource::<AwaitingBinding>();
+    let action = awaiting_binding.0;
+    if let Some(conflict) = bindings.rebind(action, binding) {
+        **conflict_text = Localized::new("controls.conflict")
+            .with_arg("action", action.label())
+            .with_arg("conflict", conflict.label())
+            .with_arg("binding", bindings.get(conflict).label());
+    }
+}
+
+fn cancel_rebinding(mut commands: Commands) {
+    commands.remove_resource::<AwaitingBinding>();
+}
+
+fn update_binding_labels(
+    mut labels: Query<(&mut Localized, &BindingLabel)>,
+    bindings: Res<InputBindings>,
+    awaiting_binding: Option<Res<AwaitingBinding>>,
+) {
+    for (mut localized, BindingLabel(action)) in &mut labels {
+        localized.set_if_neq(match &awaiting_binding {
+            Some(awaiting_binding) if awaiting_binding.0 == *action => {
+                Localized::new("controls.awaiting")
+            }
+            _ => bindings.get(*action).label(),
+        });
+    }
+}
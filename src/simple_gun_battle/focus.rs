@@ -0,0 +1,201 @@
+use bevy::{ecs::system::SystemParam, input::InputSystem, prelude::*};
+
+use crate::simple_gun_battle::input_map::AwaitingBinding;
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(
+        PreUpdate,
+        (
+            release_activated_button,
+            (navigate_focus, activate_focused).run_if(not(resource_exists::<AwaitingBinding>)),
+        )
+            .chain()
+            .after(InputSystem),
+    )
+    .add_systems(Update, focus_first_button);
+}
+
+#[derive(Component)]
+pub struct Focused;
+
+#[derive(Component, Default)]
+pub struct Adjustable;
+
+#[derive(Resource)]
+struct ActivatedButton(Entity);
+
+enum FocusMove {
+    Direction(Vec2),
+    Next,
+    Previous,
+}
+
+#[derive(SystemParam)]
+struct FocusableButtons<'w, 's> {
+    roots: Query<'w, 's, Entity, (With<Node>, Without<Parent>)>,
+    children: Query<'w, 's, &'static Children>,
+    buttons: Query<'w, 's, (&'static GlobalTransform, Has<Focused>), With<Button>>,
+}
+
+impl FocusableButtons<'_, '_> {
+    fn ordered(&self) -> Vec<(Entity, Vec2, bool)> {
+        let mut ordered = Vec::new();
+        for root in &self.roots {
+            for entity in std::iter::once(root).chain(self.children.iter_descendants(root)) {
+                if let Ok((transform, focused)) = self.buttons.get(entity) {
+                    ordered.push((entity, transform.translation().truncate(), focused));
+                }
+            }
+        }
+        ordered
+    }
+}
+
+fn release_activated_button(
+    mut commands: Commands,
+    activated: Option<Res<ActivatedButton>>,
+    mut interactions: Query<&mut Interaction>,
+) {
+    let Some(activated) = activated else {
+        return;
+    };
+    if let Ok(mut interaction) = interactions.get_mut(activated.0) {
+        if *interaction == Interaction::Pressed {
+            *interaction = Interaction::None;
+        }
+    }
+    commands.remove_resource::<ActivatedButton>();
+}
+
+fn navigate_focus(
+    mut commands: Commands,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    buttons: FocusableButtons,
+    adjustable: Query<(), (With<Focused>, With<Adjustable>)>,
+) {
+    let Some(focus_move) =
+        keyboard_focus_move(&keyboard).or_else(|| gamepads.iter().find_map(gamepad_focus_move))
+    else {
+        return;
+    };
+    if matches!(focus_move, FocusMove::Direction(direction) if direction.x != 0.)
+        && !adjustable.is_empty()
+    {
+        return;
+    }
+    let ordered = buttons.ordered();
+    if ordered.is_empty() {
+        return;
+    }
+    let next = match ordered.iter().position(|(_, _, focused)| *focused) {
+        Some(current) => {
+            commands.entity(ordered[current].0).remove::<Focused>();
+            match focus_move {
+                FocusMove::Direction(direction) => step_focus(&ordered, current, direction),
+                FocusMove::Next => (current + 1) % ordered.len(),
+                FocusMove::Previous => (current + ordered.len() - 1) % ordered.len(),
+            }
+        }
+        None => 0,
+    };
+    commands.entity(ordered[next].0).insert(Focused);
+}
+
+fn keyboard_focus_move(keyboard: &ButtonInput<KeyCode>) -> Option<FocusMove> {
+    if keyboard.just_pressed(KeyCode::Tab) {
+        return Some(
+            if keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+                FocusMove::Previous
+            } else {
+                FocusMove::Next
+            },
+        );
+    }
+    [
+        (KeyCode::ArrowUp, Vec2::NEG_Y),
+        (KeyCode::ArrowDown, Vec2::Y),
+        (KeyCode::ArrowLeft, Vec2::NEG_X),
+        (KeyCode::ArrowRight, Vec2::X),
+    ]
+    .into_iter()
+    .find_map(|(key_code, direction)| {
+        keyboard
+            .just_pressed(key_code)
+            .then_some(FocusMove::Direction(direction))
+    })
+}
+
+fn gamepad_focus_move(gamepad: &Gamepad) -> Option<FocusMove> {
+    [
+        (GamepadButton::DPadUp, Vec2::NEG_Y),
+        (GamepadButton::DPadDown, Vec2::Y),
+        (GamepadButton::DPadLeft, Vec2::NEG_X),
+        (GamepadButton::DPadRight, Vec2::X),
+    ]
+    .into_iter()
+    .find_map(|(button, direction)| {
+        gamepad
+            .just_pressed(button)
+            .then_some(FocusMove::Direction(direction))
+    })
+}
+
+fn step_focus(buttons: &[(Entity, Vec2, bool)], current: usize, direction: Vec2) -> usize {
+    let origin = buttons[current].1;
+    let nearest = buttons
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| *index != current)
+        .filter_map(|(index, (_, position, _))| {
+            let offset = *position - origin;
+            let along = offset.dot(direction);
+            (along > 1.).then(|| (index, along + offset.perp_dot(direction).abs() * 2.))
+        })
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(index, _)| index);
+    nearest.unwrap_or_else(|| {
+        let step = if direction.x + direction.y > 0. {
+            1
+        } else {
+            buttons.len() - 1
+        };
+        (current + step) % buttons.len()
+    })
+}
+
+fn activate_focused(
+    mut commands: Commands,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    mut focused: Query<(Entity, &mut Interaction), With<Focused>>,
+) {
+    let activated = keyboard.any_just_pressed([KeyCode::Enter, KeyCode::NumpadEnter])
+        || gamepads
+            .iter()
+            .any(|gamepad| gamepad.just_pressed(GamepadButton::South));
+    if !activated {
+        return;
+    }
+    if let Ok((entity, mut interaction)) = focused.get_single_mut() {
+        *interaction = Interaction::Pressed;
+        commands.insert_resource(ActivatedButton(entity));
+    }
+}
+
+fn focus_first_button(
+    mut commands: Commands,
+    added: Query<(), Added<Button>>,
+    buttons: FocusableButtons,
+) {
+    if added.is_empty() {
+        return;
+    }
+    let ordered = buttons.ordered();
+    if ordered.iter().any(|(_, _, focused)| *focused) {
+        return;
+    }
+    if let Some((first, _, _)) = ordered.first() {
+        commands.entity(*first).insert(Focused);
+    }
+}
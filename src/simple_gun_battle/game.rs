@@ -0,0 +1,157 @@
+mod game_over;
+mod inventory;
+mod level;
+mod menu;
+mod navigation;
+mod next_level;
+mod play;
+mod replay;
+mod rng;
+mod spawn;
+mod victory;
+mod weapon;
+
+use bevy::{
+    pbr::PbrPlugin,
+    prelude::*,
+    render::render_resource::{AsBindGroup, ShaderRef},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::simple_gun_battle::{ui_utils::UiCamera, AppState, GunBattleConfig};
+
+pub use level::Levels;
+pub use navigation::{NavAgent, NavMesh};
+pub use play::{
+    CharacterController, DamageEvent, DamageKind, Enemy, EnemyAi, MovementState, PlayerView,
+    Stamina, STAMINA_RECOVERED,
+};
+pub use replay::{RecordInput, ReplayInput};
+pub use rng::RngSeed;
+pub use spawn::{DespawnScenePlayer, GameScene, Player};
+pub use weapon::{Ammo, Reloading};
+
+pub fn plugin(app: &mut App) {
+    if app.is_plugin_added::<PbrPlugin>() {
+        app.add_plugins(MaterialPlugin::<HealthBarMaterial>::default());
+    } else {
+        app.init_asset::<HealthBarMaterial>();
+    }
+    app.add_plugins((
+        level::plugin,
+        navigation::plugin,
+        spawn::plugin,
+        play::plugin,
+        menu::plugin,
+        next_level::plugin,
+        game_over::plugin,
+        victory::plugin,
+        weapon::plugin,
+        inventory::plugin,
+        rng::plugin,
+        replay::plugin,
+    ))
+    .add_sub_state::<GameState>()
+    .enable_state_scoped_entities::<GameState>()
+    .add_systems(
+        OnEnter(AppState::Game),
+        (
+            insert_scene_index,
+            insert_enemy_count,
+            disable_ui_camera.never_param_warn(),
+        ),
+    )
+    .add_systems(
+        OnExit(AppState::Game),
+        (
+            remove_scene_index,
+            remove_enemy_count,
+            enable_ui_camera.never_param_warn(),
+        ),
+    );
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Hash, SubStates, Serialize, Deserialize)]
+#[source(AppState = AppState::Game)]
+pub enum GameState {
+    #[default]
+    Spawn,
+    Play,
+    Menu,
+    Settings,
+    Controls,
+    NextLevel,
+    GameOver,
+    Victory,
+}
+
+#[derive(Resource, Default)]
+pub struct SceneIndex(pub usize);
+
+#[derive(Resource, Default)]
+pub struct EnemyCount(pub usize);
+
+#[derive(Component)]
+pub struct HealthPoints {
+    maximum: f32,
+    current: f32,
+}
+
+impl HealthPoints {
+    fn new(maximum: f32) -> Self {
+        Self {
+            maximum,
+            current: maximum,
+        }
+    }
+
+    pub fn maximum(&self) -> f32 {
+        self.maximum
+    }
+
+    pub fn current(&self) -> f32 {
+        self.current
+    }
+}
+
+#[derive(Asset, TypePath, AsBindGroup, Debug, Clone)]
+struct HealthBarMaterial {
+    #[uniform(0)]
+    ratio: f32,
+}
+
+impl Material for HealthBarMaterial {
+    fn fragment_shader() -> ShaderRef {
+        "health_bar.wgsl".into()
+    }
+}
+
+fn insert_scene_index(
+    mut commands: Commands,
+    config: Res<GunBattleConfig>,
+    scene_index: Option<Res<SceneIndex>>,
+) {
+    if scene_index.is_none() {
+        commands.insert_resource(SceneIndex(config.starting_level));
+    }
+}
+
+fn remove_scene_index(mut commands: Commands) {
+    commands.remove_resource::<SceneIndex>();
+}
+
+fn insert_enemy_count(mut commands: Commands) {
+    commands.init_resource::<EnemyCount>();
+}
+
+fn remove_enemy_count(mut commands: Commands) {
+    commands.remove_resource::<EnemyCount>();
+}
+
+fn disable_ui_camera(mut ui_camera: Single<&mut Camera, With<UiCamera>>) {
+    ui_camera.is_active = false;
+}
+
+fn enable_ui_camera(mut ui_camera: Single<&mut Camera, With<UiCamera>>) {
+    ui_camera.is_active = true;
+}
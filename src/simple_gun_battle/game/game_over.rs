@@ -1,8 +1,8 @@
 use bevy::prelude::*;
 
-use crate::{ui_utils::Widgets, AppState};
+use crate::simple_gun_battle::{ui_utils::Widgets, AppState};
 
-use super::{spawn::DespawnScenePlayer, GameState, SceneIndex};
+use super::{inventory::RestoreInventory, spawn::DespawnScenePlayer, GameState};
 
 pub fn plugin(app: &mut App) {
     app.add_systems(OnEnter(GameState::GameOver), spawn_game_over)
@@ -14,7 +14,7 @@ pub fn plugin(app: &mut App) {
 
 #[derive(Component)]
 enum GameOverButton {
-    Restart,
+    Retry,
     ReturnStartMenu,
 }
 
@@ -26,9 +26,9 @@ fn spawn_game_over(mut commands: Commands) {
             GameState::GameOver,
         )
         .with_children(|parent| {
-            parent.title("游戏结束！");
-            parent.button("重新开始", GameOverButton::Restart);
-            parent.button("开始菜单", GameOverButton::ReturnStartMenu);
+            parent.title("game_over.title");
+            parent.button("game_over.retry", GameOverButton::Retry);
+            parent.button("common.start_menu", GameOverButton::ReturnStartMenu);
         });
 }
 
@@ -37,14 +37,13 @@ fn pressed_game_over(
     buttons: Query<(&Interaction, &GameOverButton), Changed<Interaction>>,
     mut game_next_state: ResMut<NextState<GameState>>,
     mut app_next_state: ResMut<NextState<AppState>>,
-    mut scene_index: ResMut<SceneIndex>,
 ) {
     for (interaction, game_over_button) in &buttons {
         if let Interaction::Pressed = interaction {
             commands.trigger(DespawnScenePlayer);
             match game_over_button {
-                GameOverButton::Restart => {
-                    scene_index.0 = 0;
+                GameOverButton::Retry => {
+                    commands.trigger(RestoreInventory);
                     game_next_state.set(GameState::Spawn);
                 }
                 GameOverButton::ReturnStartMenu => {
@@ -0,0 +1,290 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::simple_gun_battle::{
+    input_map::{Action, ActionInput},
+    AppState,
+};
+
+use super::{
+    spawn::{Gun, GunFlame, GunModel},
+    weapon::{Ammo, Reloading, WeaponDef},
+    GameState,
+};
+
+const LOADOUT: [&str; 3] = [
+    "weapons/pistol.weapon.ron",
+    "weapons/rifle.weapon.ron",
+    "weapons/shotgun.weapon.ron",
+];
+const STARTING_SLOT: usize = 1;
+const SWITCH_SECONDS: f32 = 0.4;
+const SWITCH_DROP: f32 = 0.35;
+
+pub fn plugin(app: &mut App) {
+    app.add_observer(reset_inventory)
+        .add_observer(restore_inventory)
+        .add_observer(load_inventory)
+        .add_systems(Startup, load_loadout)
+        .add_systems(OnEnter(AppState::Game), insert_inventory)
+        .add_systems(OnExit(AppState::Game), remove_inventory)
+        .add_systems(OnExit(GameState::Spawn), save_inventory_checkpoint)
+        .add_systems(
+            Update,
+            (
+                select_weapon.never_param_warn(),
+                store_active_ammo.never_param_warn(),
+            )
+                .chain()
+                .run_if(in_state(GameState::Play)),
+        )
+        .add_systems(
+            FixedUpdate,
+            switch_weapon
+                .never_param_warn()
+                .run_if(in_state(GameState::Play)),
+        )
+        .add_systems(
+            Update,
+            apply_weapon_visuals.run_if(in_state(AppState::Game)),
+        );
+}
+
+#[derive(Resource)]
+struct Loadout(Vec<Handle<WeaponDef>>);
+
+#[derive(Resource, Clone)]
+pub struct Inventory {
+    slots: Vec<InventorySlot>,
+    active: usize,
+}
+
+#[derive(Clone)]
+struct InventorySlot {
+    weapon: Handle<WeaponDef>,
+    ammo: Option<Ammo>,
+}
+
+impl Inventory {
+    fn new(loadout: &Loadout) -> Self {
+        Self {
+            slots: loadout
+                .0
+                .iter()
+                .map(|weapon| InventorySlot {
+                    weapon: weapon.clone(),
+                    ammo: None,
+                })
+                .collect(),
+            active: STARTING_SLOT.min(loadout.0.len().saturating_sub(1)),
+        }
+    }
+
+    pub fn active_weapon(&self) -> Handle<WeaponDef> {
+        self.slots[self.active].weapon.clone()
+    }
+
+    pub fn active_ammo(&self) -> Option<Ammo> {
+        self.slots[self.active].ammo
+    }
+
+    pub fn snapshot(&self) -> InventorySnapshot {
+        InventorySnapshot {
+            active: self.active,
+            ammo: self.slots.iter().map(|slot| slot.ammo).collect(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct InventorySnapshot {
+    active: usize,
+    ammo: Vec<Option<Ammo>>,
+}
+
+#[derive(Resource)]
+struct InventoryCheckpoint(Inventory);
+
+#[derive(Event)]
+pub struct ResetInventory;
+
+#[derive(Event)]
+pub struct RestoreInventory;
+
+#[derive(Event)]
+pub struct LoadInventory(pub InventorySnapshot);
+
+#[derive(Component)]
+pub struct Switching {
+    timer: Timer,
+    slot: usize,
+    swapped: bool,
+}
+
+fn load_loadout(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(Loadout(
+        LOADOUT
+            .iter()
+            .map(|path| asset_server.load(*path))
+            .collect(),
+    ));
+}
+
+fn insert_inventory(
+    mut commands: Commands,
+    loadout: Res<Loadout>,
+    inventory: Option<Res<Inventory>>,
+) {
+    if inventory.is_none() {
+        commands.insert_resource(Inventory::new(&loadout));
+    }
+}
+
+fn remove_inventory(mut commands: Commands) {
+    commands.remove_resource::<Inventory>();
+    commands.remove_resource::<InventoryCheckpoint>();
+}
+
+fn reset_inventory(
+    _trigger: Trigger<ResetInventory>,
+    mut commands: Commands,
+    loadout: Res<Loadout>,
+) {
+    commands.insert_resource(Inventory::new(&loadout));
+    commands.remove_resource::<InventoryCheckpoint>();
+}
+
+fn restore_inventory(
+    _trigger: Trigger<RestoreInventory>,
+    mut commands: Commands,
+    checkpoint: Option<Res<InventoryCheckpoint>>,
+) {
+    if let Some(checkpoint) = checkpoint {
+        commands.insert_resource(checkpoint.0.clone());
+    }
+}
+
+fn load_inventory(trigger: Trigger<LoadInventory>, mut commands: Commands, loadout: Res<Loadout>) {
+    let snapshot = &trigger.0;
+    let mut inventory = Inventory::new(&loadout);
+    if snapshot.active < inventory.slots.len() {
+        inventory.active = snapshot.active;
+    }
+    for (slot, ammo) in inventory.slots.iter_mut().zip(&snapshot.ammo) {
+        slot.ammo = *ammo;
+    }
+    commands.insert_resource(inventory);
+}
+
+fn save_inventory_checkpoint(mut commands: Commands, inventory: Res<Inventory>) {
+    commands.insert_resource(InventoryCheckpoint(inventory.clone()));
+}
+
+fn select_weapon(
+    mut commands: Commands,
+    gun: Single<(Entity, Option<&Switching>), With<Gun>>,
+    inventory: Res<Inventory>,
+    actions: ActionInput,
+) {
+    let (gun, switching) = *gun;
+    let current = switching.map_or(inventory.active, |switching| switching.slot);
+    let slot_count = inventory.slots.len();
+    let mut selected = (0..slot_count).find(|slot| {
+        u8::try_from(*slot).is_ok_and(|slot| actions.just_pressed(Action::SelectWeapon(slot)))
+    });
+    if selected.is_none() && actions.just_pressed(Action::PrevWeapon) {
+        selected = Some((current + slot_count - 1) % slot_count);
+    } else if selected.is_none() && actions.just_pressed(Action::NextWeapon) {
+        selected = Some((current + 1) % slot_count);
+    }
+    let Some(slot) = selected else {
+        return;
+    };
+    if slot == current {
+        return;
+    }
+    commands
+        .entity(gun)
+        .remove::<Reloading>()
+        .insert(Switching {
+            timer: Timer::from_seconds(SWITCH_SECONDS, TimerMode::Once),
+            slot,
+            swapped: switching.is_some_and(|switching| switching.swapped),
+        });
+}
+
+fn switch_weapon(
+    mut commands: Commands,
+    gun: Single<(Entity, &mut Gun, &mut Switching)>,
+    mut inventory: ResMut<Inventory>,
+    time: Res<Time>,
+) {
+    let (entity, mut gun, mut switching) = gun.into_inner();
+    switching.timer.tick(time.delta());
+    if !switching.swapped && switching.timer.fraction() >= 0.5 {
+        switching.swapped = true;
+        inventory.active = switching.slot;
+        gun.weapon = inventory.active_weapon();
+        match inventory.active_ammo() {
+            Some(ammo) => commands.entity(entity).insert(ammo),
+            None => commands.entity(entity).remove::<Ammo>(),
+        };
+    } else if switching.swapped && inventory.active != switching.slot {
+        switching.swapped = false;
+    }
+    if switching.timer.finished() {
+        commands.entity(entity).remove::<Switching>();
+    }
+}
+
+fn store_active_ammo(
+    gun: Single<&Ammo, (With<Gun>, Changed<Ammo>)>,
+    mut inventory: ResMut<Inventory>,
+) {
+    let active = inventory.active;
+    inventory.slots[active].ammo = Some(**gun);
+}
+
+fn apply_weapon_visuals(
+    guns: Query<(&Gun, Option<&Switching>, &Children)>,
+    mut models: Query<(&mut SceneRoot, &mut Transform), With<GunModel>>,
+    mut flames: Query<(&mut Transform, &mut GunFlame), Without<GunModel>>,
+    weapons: Res<Assets<WeaponDef>>,
+) {
+    for (gun, switching, children) in &guns {
+        let Some(weapon) = weapons.get(&gun.weapon) else {
+            continue;
+        };
+        let drop = switching.map_or(0., |switching| {
+            1. - (switching.timer.fraction() * 2. - 1.).abs()
+        });
+        for child in children {
+            if let Ok((mut scene_root, mut transform)) = models.get_mut(*child) {
+                if scene_root.0 != weapon.model {
+                    scene_root.0 = weapon.model.clone();
+                }
+                let translation = Vec3::NEG_Y * SWITCH_DROP * drop;
+                if transform.translation != translation {
+                    transform.translation = translation;
+                }
+            }
+            if let Ok((mut transform, mut gun_flame)) = flames.get_mut(*child) {
+                let scale = Vec3::splat(weapon.muzzle_flash.size);
+                if transform.translation != weapon.muzzle_flash.offset || transform.scale != scale {
+                    transform.translation = weapon.muzzle_flash.offset;
+                    transform.scale = scale;
+                }
+                let duration = Duration::from_secs_f32(weapon.muzzle_flash.seconds);
+                if gun_flame.0.duration() != duration {
+                    let finished = gun_flame.0.elapsed() >= gun_flame.0.duration();
+                    gun_flame.0.set_duration(duration);
+                    if finished {
+                        gun_flame.0.set_elapsed(duration);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,153 @@
+use std::ops::Range;
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, LoadContext},
+    ecs::system::SystemParam,
+    prelude::*,
+    utils::HashMap,
+};
+use serde::Deserialize;
+use thiserror::Error;
+
+use super::play::DamageKind;
+
+const LEVELS_PATH: &str = "campaign.levels.ron";
+
+pub fn plugin(app: &mut App) {
+    app.init_asset::<LevelManifest>()
+        .register_asset_loader(LevelManifestLoader)
+        .add_systems(Startup, load_level_manifest);
+}
+
+#[derive(Asset, TypePath, Deserialize, Debug)]
+pub struct LevelManifest {
+    pub levels: Vec<Level>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Level {
+    /// String table key of the name shown on the HUD.
+    pub name: String,
+    pub scene: String,
+    pub enemy_health: Range<f32>,
+    #[serde(default)]
+    pub enemy_resistances: HashMap<DamageKind, f32>,
+    pub player_spawn: Vec3,
+    pub win_condition: WinCondition,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WinCondition {
+    EliminateAll,
+    RemainingAtMost(usize),
+}
+
+impl WinCondition {
+    pub fn is_met(&self, enemy_count: usize) -> bool {
+        match self {
+            WinCondition::EliminateAll => enemy_count == 0,
+            WinCondition::RemainingAtMost(remaining) => enemy_count <= *remaining,
+        }
+    }
+}
+
+#[derive(Resource)]
+struct LevelManifestHandle(Handle<LevelManifest>);
+
+#[derive(SystemParam)]
+pub struct Levels<'w> {
+    handle: Res<'w, LevelManifestHandle>,
+    manifests: Res<'w, Assets<LevelManifest>>,
+}
+
+impl Levels<'_> {
+    pub fn is_loaded(&self) -> bool {
+        self.manifests.contains(&self.handle.0)
+    }
+
+    pub fn len(&self) -> usize {
+        self.manifests
+            .get(&self.handle.0)
+            .map_or(0, |manifest| manifest.levels.len())
+    }
+
+    pub fn get(&self, index: usize) -> Option<&Level> {
+        self.manifests.get(&self.handle.0)?.levels.get(index)
+    }
+
+    pub fn is_last(&self, index: usize) -> bool {
+        index + 1 >= self.len()
+    }
+}
+
+#[derive(Default)]
+struct LevelManifestLoader;
+
+#[derive(Debug, Error)]
+enum LevelManifestLoaderError {
+    #[error("could not read level manifest: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("could not parse level manifest: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+    #[error("level manifest contains no levels")]
+    NoLevels,
+    #[error("level {index} has an empty scene path")]
+    EmptyScene { index: usize },
+    #[error("level {index} has an empty enemy health range {range:?}")]
+    InvalidEnemyHealth { index: usize, range: Range<f32> },
+    #[error("level {index} resists {kind:?} damage by {resistance}, outside 0..=1")]
+    InvalidResistance {
+        index: usize,
+        kind: DamageKind,
+        resistance: f32,
+    },
+}
+
+impl AssetLoader for LevelManifestLoader {
+    type Asset = LevelManifest;
+    type Settings = ();
+    type Error = LevelManifestLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &Self::Settings,
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let manifest = ron::de::from_bytes::<LevelManifest>(&bytes)?;
+        if manifest.levels.is_empty() {
+            return Err(LevelManifestLoaderError::NoLevels);
+        }
+        for (index, level) in manifest.levels.iter().enumerate() {
+            if level.scene.is_empty() {
+                return Err(LevelManifestLoaderError::EmptyScene { index });
+            }
+            if level.enemy_health.is_empty() || level.enemy_health.start <= 0. {
+                return Err(LevelManifestLoaderError::InvalidEnemyHealth {
+                    index,
+                    range: level.enemy_health.clone(),
+                });
+            }
+            for (&kind, &resistance) in &level.enemy_resistances {
+                if !(0. ..=1.).contains(&resistance) {
+                    return Err(LevelManifestLoaderError::InvalidResistance {
+                        index,
+                        kind,
+                        resistance,
+                    });
+                }
+            }
+        }
+        Ok(manifest)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["levels.ron"]
+    }
+}
+
+fn load_level_manifest(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(LevelManifestHandle(asset_server.load(LEVELS_PATH)));
+}
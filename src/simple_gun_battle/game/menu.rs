@@ -1,11 +1,16 @@
 use bevy::prelude::*;
 
-use crate::{ui_utils::Widgets, AppState};
+use crate::simple_gun_battle::{ui_utils::Widgets, AppState};
 
 use super::{spawn::DespawnScenePlayer, GameState};
 
 pub fn plugin(app: &mut App) {
-    app.add_systems(OnEnter(GameState::Menu), spawn_menu)
+    app.add_systems(OnEnter(GameState::Menu), (spawn_menu, pause_time))
+        .add_systems(OnExit(GameState::Menu), resume_time)
+        .add_systems(OnEnter(GameState::Settings), pause_time)
+        .add_systems(OnExit(GameState::Settings), resume_time)
+        .add_systems(OnEnter(GameState::Controls), pause_time)
+        .add_systems(OnExit(GameState::Controls), resume_time)
         .add_systems(
             Update,
             pressed_menu_button.run_if(in_state(GameState::Menu)),
@@ -15,6 +20,7 @@ pub fn plugin(app: &mut App) {
 #[derive(Component)]
 enum MenuButton {
     ReturnGame,
+    Settings,
     ReturnStartMenu,
 }
 
@@ -26,12 +32,24 @@ fn spawn_menu(mut commands: Commands) {
             GameState::Menu,
         )
         .with_children(|parent| {
-            parent.title("菜单！");
-            parent.button("返回游戏", MenuButton::ReturnGame);
-            parent.button("开始菜单", MenuButton::ReturnStartMenu);
+            parent.title("menu.title");
+            parent.button("menu.return_game", MenuButton::ReturnGame);
+            parent.button("menu.settings", MenuButton::Settings);
+            parent.button("common.start_menu", MenuButton::ReturnStartMenu);
         });
 }
 
+// Fixed ticks keep running outside `GameState::Play`. Freezing virtual time
+// stops physics and timers from advancing behind menus, so a replay sees the
+// same ticks as the recording no matter how long the player sat in a menu.
+fn pause_time(mut time: ResMut<Time<Virtual>>) {
+    time.pause();
+}
+
+fn resume_time(mut time: ResMut<Time<Virtual>>) {
+    time.unpause();
+}
+
 fn pressed_menu_button(
     mut commands: Commands,
     buttons: Query<(&Interaction, &MenuButton), Changed<Interaction>>,
@@ -44,6 +62,9 @@ fn pressed_menu_button(
                 MenuButton::ReturnGame => {
                     game_next_state.set(GameState::Play);
                 }
+                MenuButton::Settings => {
+                    game_next_state.set(GameState::Settings);
+                }
                 MenuButton::ReturnStartMenu => {
                     commands.trigger(DespawnScenePlayer);
                     app_next_state.set(AppState::StartMenu);
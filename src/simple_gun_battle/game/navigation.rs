@@ -0,0 +1,448 @@
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap},
+};
+
+use avian3d::prelude::*;
+use bevy::{
+    prelude::*,
+    scene::{SceneInstance, SceneInstanceReady},
+};
+
+use crate::simple_gun_battle::AppState;
+
+use super::{
+    spawn::{DespawnScenePlayer, GameScene},
+    GameState,
+};
+
+const CELL_SIZE: f32 = 0.5;
+const AGENT_RADIUS: f32 = 0.45;
+const AGENT_HEIGHT: f32 = 1.6;
+const MAX_STEP_HEIGHT: f32 = 0.4;
+const MAX_SLOPE_DEGREES: f32 = 40.;
+const WAYPOINT_RADIUS: f32 = 0.4;
+const AVOIDANCE_DISTANCE: f32 = 1.;
+
+pub fn plugin(app: &mut App) {
+    app.add_observer(request_nav_mesh_bake)
+        .add_observer(remove_nav_mesh)
+        .add_systems(OnExit(AppState::Game), clear_nav_mesh)
+        .add_systems(
+            Update,
+            bake_nav_mesh
+                .run_if(in_state(GameState::Play).and(resource_exists::<PendingNavMeshBake>)),
+        )
+        .add_systems(
+            FixedUpdate,
+            (plan_paths, steer_agents)
+                .chain()
+                .run_if(in_state(GameState::Play).and(resource_exists::<NavMesh>)),
+        );
+}
+
+#[derive(Resource, Debug, Clone)]
+pub struct NavMesh {
+    origin: Vec2,
+    cell_size: f32,
+    size: UVec2,
+    heights: Vec<Option<f32>>,
+}
+
+impl NavMesh {
+    pub fn bake(
+        min: Vec2,
+        max: Vec2,
+        cell_size: f32,
+        mut floor_height: impl FnMut(Vec2) -> Option<f32>,
+    ) -> Self {
+        let size = ((max - min) / cell_size).ceil().max(Vec2::ONE).as_uvec2();
+        let mut nav_mesh = Self {
+            origin: min,
+            cell_size,
+            size,
+            heights: Vec::with_capacity((size.x * size.y) as usize),
+        };
+        for y in 0..size.y {
+            for x in 0..size.x {
+                let center = nav_mesh.cell_center(UVec2::new(x, y));
+                nav_mesh.heights.push(floor_height(center));
+            }
+        }
+        nav_mesh
+    }
+
+    pub fn find_path(&self, start: Vec3, goal: Vec3) -> Option<Vec<Vec3>> {
+        let start_cell = self.nearest_walkable_cell(start)?;
+        let goal_cell = self.nearest_walkable_cell(goal)?;
+
+        let mut open = BinaryHeap::new();
+        let mut came_from = HashMap::new();
+        let mut costs = HashMap::new();
+        open.push(OpenCell {
+            cell: start_cell,
+            estimate: self.distance(start_cell, goal_cell),
+        });
+        costs.insert(start_cell, 0.);
+
+        while let Some(OpenCell { cell, .. }) = open.pop() {
+            if cell == goal_cell {
+                let mut cells = vec![cell];
+                let mut current = cell;
+                while let Some(&previous) = came_from.get(&current) {
+                    cells.push(previous);
+                    current = previous;
+                }
+                cells.reverse();
+                return Some(self.smooth_path(&cells, goal));
+            }
+            let cost = costs[&cell];
+            for neighbor in self.neighbors(cell) {
+                let neighbor_cost = cost + self.distance(cell, neighbor);
+                if costs
+                    .get(&neighbor)
+                    .is_some_and(|&known_cost| known_cost <= neighbor_cost)
+                {
+                    continue;
+                }
+                costs.insert(neighbor, neighbor_cost);
+                came_from.insert(neighbor, cell);
+                open.push(OpenCell {
+                    cell: neighbor,
+                    estimate: neighbor_cost + self.distance(neighbor, goal_cell),
+                });
+            }
+        }
+        None
+    }
+
+    fn index(&self, cell: UVec2) -> usize {
+        (cell.y * self.size.x + cell.x) as usize
+    }
+
+    fn height(&self, cell: UVec2) -> Option<f32> {
+        if cell.x >= self.size.x || cell.y >= self.size.y {
+            return None;
+        }
+        self.heights[self.index(cell)]
+    }
+
+    fn cell_center(&self, cell: UVec2) -> Vec2 {
+        self.origin + (cell.as_vec2() + 0.5) * self.cell_size
+    }
+
+    fn cell_position(&self, cell: UVec2) -> Option<Vec3> {
+        let center = self.cell_center(cell);
+        Some(Vec3::new(center.x, self.height(cell)?, center.y))
+    }
+
+    fn cell_of(&self, position: Vec3) -> Option<UVec2> {
+        let cell = ((position.xz() - self.origin) / self.cell_size).floor();
+        if cell.x < 0. || cell.y < 0. {
+            return None;
+        }
+        let cell = cell.as_uvec2();
+        (cell.x < self.size.x && cell.y < self.size.y).then_some(cell)
+    }
+
+    fn nearest_walkable_cell(&self, position: Vec3) -> Option<UVec2> {
+        let center = ((position.xz() - self.origin) / self.cell_size)
+            .floor()
+            .as_ivec2();
+        let search_radius = (AGENT_RADIUS * 4. / self.cell_size).ceil() as i32;
+        (0..=search_radius).find_map(|radius| {
+            let mut ring = (-radius..=radius)
+                .flat_map(|x| (-radius..=radius).map(move |y| IVec2::new(x, y)))
+                .filter(|offset| offset.x.abs() == radius || offset.y.abs() == radius)
+                .map(|offset| center + offset)
+                .filter(|cell| cell.x >= 0 && cell.y >= 0)
+                .map(|cell| cell.as_uvec2())
+                .filter_map(|cell| Some((cell, self.height(cell)?)))
+                .filter(|(_, height)| (height - position.y).abs() <= AGENT_HEIGHT)
+                .map(|(cell, _)| cell);
+            ring.next()
+        })
+    }
+
+    fn neighbors(&self, cell: UVec2) -> impl Iterator<Item = UVec2> + '_ {
+        let height = self.height(cell);
+        [
+            IVec2::new(1, 0),
+            IVec2::new(-1, 0),
+            IVec2::new(0, 1),
+            IVec2::new(0, -1),
+            IVec2::new(1, 1),
+            IVec2::new(1, -1),
+            IVec2::new(-1, 1),
+            IVec2::new(-1, -1),
+        ]
+        .into_iter()
+        .filter_map(move |offset| {
+            let height = height?;
+            let neighbor = cell.as_ivec2() + offset;
+            if neighbor.x < 0 || neighbor.y < 0 {
+                return None;
+            }
+            let neighbor = neighbor.as_uvec2();
+            let reachable = |to: UVec2| {
+                self.height(to)
+                    .is_some_and(|to_height| (to_height - height).abs() <= MAX_STEP_HEIGHT)
+            };
+            let cuts_corner = offset.x != 0
+                && offset.y != 0
+                && !(reachable((cell.as_ivec2() + IVec2::new(offset.x, 0)).as_uvec2())
+                    && reachable((cell.as_ivec2() + IVec2::new(0, offset.y)).as_uvec2()));
+            (reachable(neighbor) && !cuts_corner).then_some(neighbor)
+        })
+    }
+
+    fn distance(&self, from: UVec2, to: UVec2) -> f32 {
+        from.as_vec2().distance(to.as_vec2()) * self.cell_size
+    }
+
+    fn smooth_path(&self, cells: &[UVec2], goal: Vec3) -> Vec<Vec3> {
+        let points: Vec<Vec3> = cells
+            .iter()
+            .filter_map(|&cell| self.cell_position(cell))
+            .collect();
+        let mut path = Vec::new();
+        let mut anchor = 0;
+        while anchor + 1 < points.len() {
+            let mut next = anchor + 1;
+            while next + 1 < points.len() && self.is_line_walkable(points[anchor], points[next + 1])
+            {
+                next += 1;
+            }
+            path.push(points[next]);
+            anchor = next;
+        }
+        if let Some(last) = path.last_mut() {
+            if self.is_line_walkable(*last, goal) {
+                *last = goal.with_y(last.y);
+            }
+        }
+        path
+    }
+
+    fn is_line_walkable(&self, from: Vec3, to: Vec3) -> bool {
+        let steps = (from.xz().distance(to.xz()) / (self.cell_size * 0.5)).ceil() as usize;
+        let mut previous_height = from.y;
+        (0..=steps.max(1)).all(|step| {
+            let point = from.lerp(to, step as f32 / steps.max(1) as f32);
+            let Some(height) = self.cell_of(point).and_then(|cell| self.height(cell)) else {
+                return false;
+            };
+            let step_height = (height - previous_height).abs();
+            previous_height = height;
+            step_height <= MAX_STEP_HEIGHT
+                && self.neighbor_cells_walkable(point, AGENT_RADIUS * 0.5)
+        })
+    }
+
+    fn neighbor_cells_walkable(&self, point: Vec3, radius: f32) -> bool {
+        [Vec3::X, Vec3::NEG_X, Vec3::Z, Vec3::NEG_Z]
+            .into_iter()
+            .all(|offset| {
+                self.cell_of(point + offset * radius)
+                    .and_then(|cell| self.height(cell))
+                    .is_some()
+            })
+    }
+}
+
+#[derive(PartialEq)]
+struct OpenCell {
+    cell: UVec2,
+    estimate: f32,
+}
+
+impl Eq for OpenCell {}
+
+impl Ord for OpenCell {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.estimate.total_cmp(&self.estimate)
+    }
+}
+
+impl PartialOrd for OpenCell {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[derive(Component, Debug, Clone)]
+pub struct NavAgent {
+    pub speed: f32,
+    destination: Option<Vec3>,
+    path: Vec<Vec3>,
+}
+
+impl NavAgent {
+    pub fn new(speed: f32) -> Self {
+        Self {
+            speed,
+            destination: None,
+            path: Vec::new(),
+        }
+    }
+
+    pub fn set_destination(&mut self, destination: Option<Vec3>) {
+        let unchanged = match (self.destination, destination) {
+            (Some(current), Some(new)) => current.distance(new) < WAYPOINT_RADIUS,
+            (None, None) => true,
+            _ => false,
+        };
+        if !unchanged {
+            self.destination = destination;
+            self.path.clear();
+        }
+    }
+}
+
+#[derive(Resource)]
+struct PendingNavMeshBake;
+
+fn request_nav_mesh_bake(
+    trigger: Trigger<SceneInstanceReady>,
+    mut commands: Commands,
+    game_scene: Single<&SceneInstance, With<GameScene>>,
+) {
+    if trigger.instance_id == ***game_scene {
+        commands.remove_resource::<NavMesh>();
+        commands.insert_resource(PendingNavMeshBake);
+    }
+}
+
+fn remove_nav_mesh(_trigger: Trigger<DespawnScenePlayer>, mut commands: Commands) {
+    commands.remove_resource::<NavMesh>();
+    commands.remove_resource::<PendingNavMeshBake>();
+}
+
+fn clear_nav_mesh(mut commands: Commands) {
+    commands.remove_resource::<NavMesh>();
+    commands.remove_resource::<PendingNavMeshBake>();
+}
+
+fn bake_nav_mesh(
+    mut commands: Commands,
+    static_bodies: Query<(Entity, &RigidBody, &ColliderAabb)>,
+    spatial_query: SpatialQuery,
+) {
+    let mut bounds: Option<(Vec3, Vec3)> = None;
+    for (_, rigid_body, aabb) in &static_bodies {
+        if !rigid_body.is_static() || aabb.size() == Vec3::ZERO {
+            continue;
+        }
+        bounds = Some(bounds.map_or((aabb.min, aabb.max), |(min, max)| {
+            (min.min(aabb.min), max.max(aabb.max))
+        }));
+    }
+    let Some((min, max)) = bounds else {
+        return;
+    };
+
+    let is_static = |entity: Entity| {
+        static_bodies
+            .get(entity)
+            .is_ok_and(|(_, rigid_body, _)| rigid_body.is_static())
+    };
+    let clearance = Collider::cylinder(AGENT_RADIUS, AGENT_HEIGHT - MAX_STEP_HEIGHT);
+    let filter = SpatialQueryFilter::default();
+    let max_slope_cos = MAX_SLOPE_DEGREES.to_radians().cos();
+    let nav_mesh = NavMesh::bake(min.xz(), max.xz(), CELL_SIZE, |point| {
+        let origin = Vec3::new(point.x, max.y + 1., point.y);
+        let hit = spatial_query.cast_ray_predicate(
+            origin,
+            Dir3::NEG_Y,
+            max.y - min.y + 2.,
+            true,
+            &filter,
+            &is_static,
+        )?;
+        if hit.normal.y < max_slope_cos {
+            return None;
+        }
+        let floor = origin.y - hit.distance;
+        let blocked = spatial_query
+            .shape_intersections(
+                &clearance,
+                Vec3::new(
+                    point.x,
+                    floor + MAX_STEP_HEIGHT + (AGENT_HEIGHT - MAX_STEP_HEIGHT) / 2.,
+                    point.y,
+                ),
+                Quat::IDENTITY,
+                &filter,
+            )
+            .into_iter()
+            .any(is_static);
+        (!blocked).then_some(floor)
+    });
+    commands.insert_resource(nav_mesh);
+    commands.remove_resource::<PendingNavMeshBake>();
+}
+
+fn plan_paths(
+    mut agents: Query<(&Position, &mut NavAgent, &mut LinearVelocity)>,
+    nav_mesh: Res<NavMesh>,
+) {
+    for (position, mut agent, mut linear_velocity) in &mut agents {
+        let Some(destination) = agent.destination else {
+            continue;
+        };
+        if !agent.path.is_empty() {
+            continue;
+        }
+        match nav_mesh.find_path(position.0, destination) {
+            Some(mut path) if !path.is_empty() => {
+                path.reverse();
+                agent.path = path;
+            }
+            _ => {
+                agent.destination = None;
+                linear_velocity.x = 0.;
+                linear_velocity.z = 0.;
+            }
+        }
+    }
+}
+
+fn steer_agents(
+    mut agents: Query<(Entity, &Position, &mut NavAgent, &mut LinearVelocity)>,
+    spatial_query: SpatialQuery,
+) {
+    for (entity, position, mut agent, mut linear_velocity) in &mut agents {
+        let Some(&waypoint) = agent.path.last() else {
+            linear_velocity.x = 0.;
+            linear_velocity.z = 0.;
+            continue;
+        };
+        let offset = (waypoint - position.0).with_y(0.);
+        if offset.length() < WAYPOINT_RADIUS {
+            agent.path.pop();
+            if agent.path.is_empty() {
+                agent.destination = None;
+                linear_velocity.x = 0.;
+                linear_velocity.z = 0.;
+            }
+            continue;
+        }
+        let Ok(direction) = Dir3::new(offset) else {
+            continue;
+        };
+        let mut steering = *direction;
+        if let Some(hit) = spatial_query.cast_ray(
+            position.0,
+            direction,
+            AVOIDANCE_DISTANCE,
+            true,
+            &SpatialQueryFilter::from_excluded_entities([entity]),
+        ) {
+            let push = 1. - hit.distance / AVOIDANCE_DISTANCE;
+            steering += hit.normal.with_y(0.) * push;
+        }
+        let velocity = steering.normalize_or_zero() * agent.speed;
+        linear_velocity.x = velocity.x;
+        linear_velocity.z = velocity.z;
+    }
+}
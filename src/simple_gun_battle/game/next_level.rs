@@ -1,6 +1,6 @@
 use bevy::prelude::*;
 
-use crate::{ui_utils::Widgets, AppState};
+use crate::simple_gun_battle::{ui_utils::Widgets, AppState};
 
 use super::{spawn::DespawnScenePlayer, GameState, SceneIndex};
 
@@ -26,9 +26,9 @@ fn spawn_next_level(mut commands: Commands) {
             GameState::NextLevel,
         )
         .with_children(|parent| {
-            parent.title("结算！");
-            parent.button("下一关", NextLevelButton::NextLevel);
-            parent.button("开始菜单", NextLevelButton::ReturnStartMenu);
+            parent.title("next_level.title");
+            parent.button("next_level.next_level", NextLevelButton::NextLevel);
+            parent.button("common.start_menu", NextLevelButton::ReturnStartMenu);
         });
 }
 
@@ -0,0 +1,299 @@
+mod action;
+mod ammo;
+mod controller;
+mod damage;
+mod enemy;
+mod movement;
+mod spawn;
+mod view;
+
+use core::f32;
+
+use avian3d::prelude::*;
+use bevy::{pbr::NotShadowCaster, prelude::*, window::PrimaryWindow};
+use damage::{Damaged, Died, Hazard, Resistances};
+use rand::Rng;
+use spawn::{EnemyCountText, PlayerHealthText};
+
+use crate::simple_gun_battle::input_map::{Action, ActionInput};
+
+use super::{
+    level::Levels, navigation::NavAgent, rng::GameRng, spawn::Player, EnemyCount, GameState,
+    HealthBarMaterial, HealthPoints, SceneIndex,
+};
+
+pub use controller::CharacterController;
+pub use damage::{DamageEvent, DamageKind};
+pub use enemy::EnemyAi;
+pub use movement::{standing_collider, MovementState, Stamina, STAMINA_RECOVERED};
+pub use view::PlayerView;
+
+const HAZARD_DAMAGE_PER_SECOND: f32 = 20.;
+const ENEMY_SPEED: f32 = 4.;
+
+pub fn plugin(app: &mut App) {
+    app.add_plugins((
+        spawn::plugin,
+        action::plugin,
+        ammo::plugin,
+        controller::plugin,
+        damage::plugin,
+        enemy::plugin,
+        movement::plugin,
+        view::plugin,
+    ))
+    .add_observer(update_enemy_count)
+    .add_observer(count_remaining_enemies)
+    .add_observer(pass_level)
+    .add_observer(despawn_dead_enemy)
+    .add_observer(update_health_bar)
+    .add_systems(
+        OnEnter(GameState::Play),
+        (hide_cursor.never_param_warn(), enter_update_enemy_count),
+    )
+    .add_systems(OnExit(GameState::Play), show_cursor.never_param_warn())
+    .add_systems(
+        Update,
+        (
+            lock_cursor.never_param_warn(),
+            update_enemy_count_text,
+            update_player_health_text.never_param_warn(),
+            play_to_menu,
+            health_bar_align_player_camera.never_param_warn(),
+        )
+            .run_if(in_state(GameState::Play)),
+    )
+    .add_systems(
+        Update,
+        (
+            spawn_scene_enemy_rigid_bodies.never_param_warn(),
+            enable_shadows.never_param_warn(),
+        )
+            .run_if(in_state(GameState::Play)),
+    );
+}
+
+#[derive(Component)]
+pub struct Enemy;
+
+#[derive(Component)]
+struct HealthBar;
+
+#[derive(Event)]
+struct UpdateEnemyCount;
+
+#[derive(Event, Clone, Copy)]
+struct FireBullet {
+    origin: Vec3,
+    direction: Dir3,
+    speed: f32,
+    damage: f32,
+    shooter: Entity,
+}
+
+#[derive(Event, Clone, Copy)]
+struct FireHitscan {
+    origin: Vec3,
+    direction: Dir3,
+    range: f32,
+    damage: f32,
+    shooter: Entity,
+}
+
+#[derive(Event)]
+struct GunFired {
+    sound: Handle<AudioSource>,
+}
+
+fn hide_cursor(mut window: Single<&mut Window, With<PrimaryWindow>>) {
+    window.cursor_options.visible = false;
+}
+
+fn show_cursor(mut window: Single<&mut Window, With<PrimaryWindow>>) {
+    window.cursor_options.visible = true;
+}
+
+fn enter_update_enemy_count(mut commands: Commands) {
+    commands.trigger(UpdateEnemyCount);
+}
+
+fn lock_cursor(mut window: Single<&mut Window, With<PrimaryWindow>>) {
+    let cursor_position = Vec2::new(window.width() / 2., window.height() / 2.);
+    window.set_cursor_position(Some(cursor_position));
+}
+
+fn update_enemy_count_text(
+    mut enemy_count_text: Single<&mut TextSpan, With<EnemyCountText>>,
+    enemy_count: Res<EnemyCount>,
+) {
+    if enemy_count.is_changed() {
+        enemy_count_text.0 = enemy_count.0.to_string();
+    }
+}
+
+fn update_player_health_text(
+    mut player_health_text: Single<&mut TextSpan, With<PlayerHealthText>>,
+    player: Single<&HealthPoints, With<Player>>,
+) {
+    let health = format!("{:.0}", player.current.ceil());
+    if player_health_text.0 != health {
+        player_health_text.0 = health;
+    }
+}
+
+fn pass_level(
+    trigger: Trigger<Died>,
+    enemies: Query<Entity, With<Enemy>>,
+    mut next_state: ResMut<NextState<GameState>>,
+    scene_index: Res<SceneIndex>,
+    levels: Levels,
+) {
+    if !enemies.contains(trigger.entity()) {
+        return;
+    }
+    let Some(level) = levels.get(scene_index.0) else {
+        return;
+    };
+    if !level
+        .win_condition
+        .is_met(remaining_enemies(&enemies, trigger.entity()))
+    {
+        return;
+    }
+    if !levels.is_last(scene_index.0) {
+        next_state.set(GameState::NextLevel);
+    } else {
+        next_state.set(GameState::Victory);
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn spawn_scene_enemy_rigid_bodies(
+    mut rectangle: Local<Handle<Mesh>>,
+    mut commands: Commands,
+    rigid_bodies: Populated<(Entity, &Name), Added<Collider>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<HealthBarMaterial>>,
+    mut rng: ResMut<GameRng>,
+    scene_index: Res<SceneIndex>,
+    levels: Levels,
+) {
+    if *rectangle == Handle::default() {
+        *rectangle = meshes.add(Rectangle::new(1.5, 0.15));
+    }
+    let enemy_health = levels
+        .get(scene_index.0)
+        .map_or(27.0..53.0, |level| level.enemy_health.clone());
+    let enemy_resistances = levels
+        .get(scene_index.0)
+        .map(|level| level.enemy_resistances.clone())
+        .unwrap_or_default();
+
+    for (entity, name) in rigid_bodies.iter() {
+        if name.as_str() == "enemy_mesh" {
+            let health_points = rng.gen_range(enemy_health.clone());
+            commands
+                .entity(entity)
+                .insert((
+                    RigidBody::Dynamic,
+                    LockedAxes::ROTATION_LOCKED,
+                    Enemy,
+                    HealthPoints::new(health_points),
+                    Resistances(enemy_resistances.clone()),
+                    EnemyAi::default(),
+                    NavAgent::new(ENEMY_SPEED),
+                ))
+                .with_child((
+                    Mesh3d(rectangle.clone()),
+                    MeshMaterial3d(materials.add(HealthBarMaterial { ratio: 1. })),
+                    NotShadowCaster,
+                    Transform::from_xyz(0., 1.25, 0.),
+                    HealthBar,
+                ));
+        } else if name.starts_with("hazard") {
+            commands.entity(entity).insert((
+                RigidBody::Static,
+                Hazard {
+                    damage_per_second: HAZARD_DAMAGE_PER_SECOND,
+                },
+            ));
+        } else {
+            commands.entity(entity).insert(RigidBody::Static);
+        }
+    }
+    commands.trigger(UpdateEnemyCount);
+}
+
+fn enable_shadows(mut light: Single<&mut DirectionalLight, Added<DirectionalLight>>) {
+    light.shadows_enabled = true;
+}
+
+fn update_enemy_count(
+    _trigger: Trigger<UpdateEnemyCount>,
+    enemies: Query<(), With<Enemy>>,
+    mut enemy_count: ResMut<EnemyCount>,
+) {
+    enemy_count.0 = enemies.iter().count();
+}
+
+fn count_remaining_enemies(
+    trigger: Trigger<Died>,
+    enemies: Query<Entity, With<Enemy>>,
+    mut enemy_count: ResMut<EnemyCount>,
+) {
+    if enemies.contains(trigger.entity()) {
+        enemy_count.0 = remaining_enemies(&enemies, trigger.entity());
+    }
+}
+
+fn remaining_enemies(enemies: &Query<Entity, With<Enemy>>, dead: Entity) -> usize {
+    enemies.iter().filter(|enemy| *enemy != dead).count()
+}
+
+fn despawn_dead_enemy(
+    trigger: Trigger<Died>,
+    mut commands: Commands,
+    enemies: Query<(), With<Enemy>>,
+) {
+    if enemies.contains(trigger.entity()) {
+        commands.entity(trigger.entity()).try_despawn_recursive();
+    }
+}
+
+fn play_to_menu(actions: ActionInput, mut next_state: ResMut<NextState<GameState>>) {
+    if actions.just_pressed(Action::Pause) {
+        next_state.set(GameState::Menu);
+    }
+}
+
+fn health_bar_align_player_camera(
+    mut health_bars: Query<(&mut Transform, &GlobalTransform), With<HealthBar>>,
+    player: Single<&GlobalTransform, With<Player>>,
+) {
+    for (mut health_transform, health_global_transform) in &mut health_bars {
+        let camera_translation = health_global_transform
+            .affine()
+            .inverse()
+            .transform_point3(player.translation());
+        let camera_translation = health_transform.transform_point(camera_translation);
+        health_transform.align(Vec3::Z, camera_translation.normalize(), Vec3::Y, Vec3::Y);
+    }
+}
+
+fn update_health_bar(
+    trigger: Trigger<Damaged>,
+    enemies: Query<(&Children, &HealthPoints), With<Enemy>>,
+    health_bars: Query<&MeshMaterial3d<HealthBarMaterial>, With<HealthBar>>,
+    mut materials: ResMut<Assets<HealthBarMaterial>>,
+) {
+    let Ok((children, health_points)) = enemies.get(trigger.entity()) else {
+        return;
+    };
+    let Ok(material) = health_bars.get(children[0]) else {
+        return;
+    };
+    let Some(material) = materials.get_mut(material) else {
+        return;
+    };
+    material.ratio = health_points.current / health_points.maximum;
+}
@@ -0,0 +1,391 @@
+use avian3d::prelude::*;
+use bevy::{color::palettes::css, pbr::NotShadowCaster, prelude::*};
+use rand::Rng;
+
+use crate::simple_gun_battle::{
+    game::{
+        inventory::Switching,
+        rng::GameRng,
+        spawn::{Gun, GunFlame, Player, PlayerCamera},
+        weapon::{Ammo, Ballistics, FireControl, FireMode, Reloading, WeaponDef},
+        GameState,
+    },
+    input_map::{Action, ActionInput},
+};
+
+use super::{
+    ammo::StartReload,
+    controller::CharacterController,
+    damage::{DamageEvent, DamageKind},
+    movement::{MovementInput, MovementState},
+    FireBullet, FireHitscan, GunFired,
+};
+
+const JUMP_BUFFER_SECONDS: f32 = 0.15;
+const TRIGGER_BUFFER_SECONDS: f32 = 0.15;
+const MUZZLE_DISTANCE: f32 = 1.5;
+const TRACER_SECONDS: f32 = 0.08;
+const TRACER_WIDTH: f32 = 0.02;
+
+pub fn plugin(app: &mut App) {
+    app.add_observer(spawn_bullet)
+        .add_observer(fire_hitscan)
+        .add_observer(spawn_tracer)
+        .add_observer(spawn_gunfire)
+        .add_observer(reset_gun_flame)
+        .add_systems(
+            Update,
+            (
+                move_and_jump,
+                buffer_fire_input.never_param_warn(),
+                detect_bullet_collision,
+                bullet_beyond_the_limit,
+                fade_tracers,
+                spawn_gun_flame,
+            )
+                .run_if(in_state(GameState::Play)),
+        )
+        .add_systems(
+            FixedUpdate,
+            fire.never_param_warn().run_if(in_state(GameState::Play)),
+        );
+}
+
+#[derive(Component)]
+struct Bullet {
+    shooter: Entity,
+    damage: f32,
+}
+
+#[derive(Component)]
+struct Tracer(Timer);
+
+#[derive(Event, Clone, Copy)]
+struct SpawnTracer {
+    origin: Vec3,
+    direction: Dir3,
+    length: f32,
+}
+
+fn move_and_jump(
+    player: Single<(&Transform, &mut MovementInput, &mut CharacterController), With<Player>>,
+    actions: ActionInput,
+) {
+    let (transform, mut input, mut controller) = player.into_inner();
+    let movement = actions.movement();
+    input.direction = (transform.left() * movement.x + transform.back() * movement.y)
+        .with_y(0.)
+        .clamp_length_max(1.);
+    input.sprint = actions.pressed(Action::Sprint);
+    input.crouch = actions.pressed(Action::Crouch);
+    if actions.just_pressed(Action::Jump) {
+        controller.jump_buffer = JUMP_BUFFER_SECONDS;
+    }
+}
+
+fn buffer_fire_input(mut fire_control: Single<&mut FireControl>, actions: ActionInput) {
+    if actions.just_pressed(Action::Fire) {
+        fire_control.trigger_buffer = TRIGGER_BUFFER_SECONDS;
+    }
+    fire_control.trigger_held = actions.pressed(Action::Fire);
+}
+
+fn spawn_gunfire(
+    trigger: Trigger<GunFired>,
+    mut commands: Commands,
+    gun: Single<Entity, With<Gun>>,
+) {
+    commands.entity(*gun).with_child((
+        AudioPlayer(trigger.sound.clone()),
+        PlaybackSettings::DESPAWN.with_spatial(true),
+        Transform::from_xyz(0., 0., 1.5),
+    ));
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn fire(
+    mut commands: Commands,
+    player: Single<(Entity, &MovementState), With<Player>>,
+    player_camera: Single<&GlobalTransform, With<PlayerCamera>>,
+    gun: Single<(
+        Entity,
+        &Gun,
+        &mut FireControl,
+        &mut Ammo,
+        Has<Reloading>,
+        Has<Switching>,
+    )>,
+    weapons: Res<Assets<WeaponDef>>,
+    mut rng: ResMut<GameRng>,
+    time: Res<Time>,
+) {
+    let (gun_entity, gun, mut fire_control, mut ammo, reloading, switching) = gun.into_inner();
+    let (player, movement_state) = *player;
+    let just_pulled = fire_control.trigger_buffer > 0.;
+    fire_control.trigger_buffer = (fire_control.trigger_buffer - time.delta_secs()).max(0.);
+    let Some(weapon) = weapons.get(&gun.weapon) else {
+        return;
+    };
+    fire_control.cooldown = (fire_control.cooldown - time.delta_secs()).max(0.);
+    if reloading || switching {
+        fire_control.burst_remaining = 0;
+        return;
+    }
+    let trigger_pulled = match weapon.fire_mode {
+        FireMode::SemiAuto => just_pulled,
+        FireMode::FullAuto => fire_control.trigger_held || just_pulled,
+        FireMode::Burst(rounds) => {
+            if just_pulled && fire_control.burst_remaining == 0 {
+                fire_control.burst_remaining = rounds;
+                fire_control.trigger_buffer = 0.;
+            }
+            fire_control.burst_remaining > 0
+        }
+    };
+    if !trigger_pulled || fire_control.cooldown > 0. {
+        return;
+    }
+    fire_control.trigger_buffer = 0.;
+    if ammo.magazine == 0 {
+        fire_control.burst_remaining = 0;
+        if just_pulled {
+            commands.entity(gun_entity).with_child((
+                AudioPlayer(weapon.empty_sound.clone()),
+                PlaybackSettings::DESPAWN.with_spatial(true),
+                Transform::from_xyz(0., 0., 0.5),
+            ));
+            commands.trigger(StartReload);
+        }
+        return;
+    }
+    ammo.magazine -= 1;
+    fire_control.cooldown += 1. / weapon.fire_rate;
+    fire_control.burst_remaining = fire_control.burst_remaining.saturating_sub(1);
+
+    let spread = (weapon.spread_degrees * movement_state.spread_scale()).to_radians();
+    for _ in 0..weapon.pellets {
+        let direction = if spread > 0. {
+            Quat::from_euler(
+                EulerRot::YXZ,
+                rng.gen_range(-spread..spread),
+                rng.gen_range(-spread..spread),
+                0.,
+            ) * player_camera.forward()
+        } else {
+            player_camera.forward()
+        };
+        let damage = rng.gen_range(weapon.damage.clone());
+        match weapon.ballistics {
+            Ballistics::Projectile { muzzle_velocity } => commands.trigger(FireBullet {
+                origin: player_camera.translation() + direction * MUZZLE_DISTANCE,
+                direction,
+                speed: muzzle_velocity,
+                damage,
+                shooter: player,
+            }),
+            Ballistics::Hitscan { range } => commands.trigger(FireHitscan {
+                origin: player_camera.translation(),
+                direction,
+                range,
+                damage,
+                shooter: player,
+            }),
+        }
+    }
+    commands.trigger(GunFired {
+        sound: weapon.sound.clone(),
+    });
+}
+
+fn spawn_bullet(
+    trigger: Trigger<FireBullet>,
+    mut bullet_mesh: Local<Handle<Mesh>>,
+    mut bullet_material: Local<Handle<StandardMaterial>>,
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    if *bullet_mesh == Handle::<Mesh>::default() {
+        *bullet_mesh = meshes.add(Capsule3d::new(0.03, 0.1));
+    }
+    if *bullet_material == Handle::<StandardMaterial>::default() {
+        *bullet_material = materials.add(StandardMaterial {
+            emissive: Color::Srgba(css::ORANGE_RED).to_linear().with_luminance(3.),
+            ..default()
+        });
+    }
+
+    let FireBullet {
+        origin,
+        direction,
+        speed,
+        damage,
+        shooter,
+    } = *trigger.event();
+    commands.spawn((
+        Mesh3d(bullet_mesh.clone()),
+        MeshMaterial3d(bullet_material.clone()),
+        Transform::from_translation(origin)
+            .with_rotation(Quat::from_rotation_arc(Vec3::Y, *direction)),
+        RigidBody::Kinematic,
+        LinearVelocity(direction * speed),
+        Collider::capsule(0.03, 0.1),
+        SweptCcd::LINEAR,
+        Sensor,
+        Bullet { shooter, damage },
+    ));
+}
+
+fn fire_hitscan(
+    trigger: Trigger<FireHitscan>,
+    mut commands: Commands,
+    spatial_query: SpatialQuery,
+    bullets: Query<(), With<Bullet>>,
+) {
+    let FireHitscan {
+        origin,
+        direction,
+        range,
+        damage,
+        shooter,
+    } = *trigger.event();
+    let hit = spatial_query.cast_ray_predicate(
+        origin,
+        direction,
+        range,
+        true,
+        &SpatialQueryFilter::from_excluded_entities([shooter]),
+        &|entity| !bullets.contains(entity),
+    );
+    let length = hit.map_or(range, |hit| hit.distance) - MUZZLE_DISTANCE;
+    if length > 0. {
+        commands.trigger(SpawnTracer {
+            origin: origin + direction * MUZZLE_DISTANCE,
+            direction,
+            length,
+        });
+    }
+
+    if let Some(hit) = hit {
+        commands.trigger(DamageEvent {
+            target: hit.entity,
+            source: Some(shooter),
+            amount: damage,
+            kind: DamageKind::Ballistic,
+            hit_point: Some(origin + direction * hit.distance),
+        });
+    }
+}
+
+fn spawn_tracer(
+    trigger: Trigger<SpawnTracer>,
+    mut tracer_mesh: Local<Handle<Mesh>>,
+    mut tracer_material: Local<Handle<StandardMaterial>>,
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    if *tracer_mesh == Handle::<Mesh>::default() {
+        *tracer_mesh = meshes.add(Cuboid::new(1., 1., 1.));
+    }
+    if *tracer_material == Handle::<StandardMaterial>::default() {
+        *tracer_material = materials.add(StandardMaterial {
+            emissive: Color::Srgba(css::ORANGE_RED).to_linear().with_luminance(3.),
+            unlit: true,
+            ..default()
+        });
+    }
+
+    let SpawnTracer {
+        origin,
+        direction,
+        length,
+    } = *trigger.event();
+    commands.spawn((
+        Mesh3d(tracer_mesh.clone()),
+        MeshMaterial3d(tracer_material.clone()),
+        Transform::from_translation(origin + direction * length / 2.)
+            .looking_to(direction, Vec3::Y)
+            .with_scale(Vec3::new(TRACER_WIDTH, TRACER_WIDTH, length)),
+        NotShadowCaster,
+        Tracer(Timer::from_seconds(TRACER_SECONDS, TimerMode::Once)),
+        StateScoped(GameState::Play),
+    ));
+}
+
+fn fade_tracers(
+    mut commands: Commands,
+    mut tracers: Query<(Entity, &mut Transform, &mut Tracer)>,
+    time: Res<Time>,
+) {
+    for (entity, mut transform, mut tracer) in &mut tracers {
+        if tracer.0.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        let width = TRACER_WIDTH * tracer.0.fraction_remaining();
+        transform.scale.x = width;
+        transform.scale.y = width;
+    }
+}
+
+fn detect_bullet_collision(
+    mut commands: Commands,
+    bullets: Query<(&Bullet, &Position)>,
+    mut collision_reader: EventReader<Collision>,
+) {
+    for Collision(contacts) in collision_reader.read() {
+        let (bullet, hit_object) = if bullets.contains(contacts.entity1) {
+            (contacts.entity1, contacts.entity2)
+        } else if bullets.contains(contacts.entity2) {
+            (contacts.entity2, contacts.entity1)
+        } else {
+            continue;
+        };
+        let Ok((&Bullet { shooter, damage }, position)) = bullets.get(bullet) else {
+            continue;
+        };
+        if hit_object == shooter || bullets.contains(hit_object) {
+            continue;
+        }
+        commands.entity(bullet).try_despawn();
+        commands.trigger(DamageEvent {
+            target: hit_object,
+            source: Some(shooter),
+            amount: damage,
+            kind: DamageKind::Ballistic,
+            hit_point: Some(position.0),
+        });
+    }
+}
+
+fn bullet_beyond_the_limit(
+    mut commands: Commands,
+    bullets: Query<(Entity, &Position), With<Bullet>>,
+) {
+    for (entity, position) in &bullets {
+        if position.length() > 300. {
+            commands.entity(entity).try_despawn();
+        }
+    }
+}
+
+fn spawn_gun_flame(
+    mut gun_flame: Single<(&MeshMaterial3d<StandardMaterial>, &mut GunFlame)>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    time: Res<Time>,
+) {
+    let material = gun_flame.0;
+    let gun_flame = &mut gun_flame.1;
+    if !gun_flame.0.tick(time.delta()).finished() {
+        if let Some(material) = materials.get_mut(&material.0) {
+            material
+                .base_color
+                .set_alpha(gun_flame.0.fraction_remaining());
+        }
+    }
+}
+
+fn reset_gun_flame(_trigger: Trigger<GunFired>, mut gun_flame: Single<&mut GunFlame>) {
+    gun_flame.0.reset();
+}
@@ -0,0 +1,145 @@
+use bevy::prelude::*;
+
+use crate::simple_gun_battle::{
+    game::{
+        inventory::Switching,
+        spawn::{Gun, Player},
+        weapon::{Ammo, Reloading, WeaponDef},
+        GameState,
+    },
+    input_map::{Action, ActionInput},
+    locale::{Localized, Localizer},
+};
+
+use super::{movement::MovementState, spawn::AmmoText};
+
+pub fn plugin(app: &mut App) {
+    app.add_observer(start_reload)
+        .add_systems(
+            Update,
+            (
+                init_ammo,
+                reload_input,
+                interrupt_reload.never_param_warn(),
+                update_ammo_text.never_param_warn(),
+            )
+                .chain()
+                .run_if(in_state(GameState::Play)),
+        )
+        .add_systems(FixedUpdate, finish_reload.run_if(in_state(GameState::Play)));
+}
+
+#[derive(Event)]
+pub struct StartReload;
+
+fn init_ammo(
+    mut commands: Commands,
+    guns: Query<(Entity, &Gun), Without<Ammo>>,
+    weapons: Res<Assets<WeaponDef>>,
+) {
+    for (entity, gun) in &guns {
+        let Some(weapon) = weapons.get(&gun.weapon) else {
+            continue;
+        };
+        commands.entity(entity).insert(Ammo {
+            magazine: weapon.magazine_size,
+            reserve: weapon.reserve_ammo,
+        });
+    }
+}
+
+fn reload_input(mut commands: Commands, actions: ActionInput) {
+    if actions.just_pressed(Action::Reload) {
+        commands.trigger(StartReload);
+    }
+}
+
+fn start_reload(
+    _trigger: Trigger<StartReload>,
+    mut commands: Commands,
+    guns: Query<(Entity, &Gun, &Ammo, Has<Switching>), Without<Reloading>>,
+    player: Query<&MovementState, With<Player>>,
+    weapons: Res<Assets<WeaponDef>>,
+) {
+    let sprinting = player
+        .iter()
+        .any(|state| *state == MovementState::Sprinting);
+    for (entity, gun, ammo, switching) in &guns {
+        let Some(weapon) = weapons.get(&gun.weapon) else {
+            continue;
+        };
+        if !switching && !sprinting && ammo.magazine < weapon.magazine_size && ammo.reserve > 0 {
+            commands
+                .entity(entity)
+                .insert(Reloading(Timer::from_seconds(
+                    weapon.reload_seconds,
+                    TimerMode::Once,
+                )));
+        }
+    }
+}
+
+fn interrupt_reload(
+    mut commands: Commands,
+    player: Single<&MovementState, With<Player>>,
+    guns: Query<Entity, With<Reloading>>,
+) {
+    if **player != MovementState::Sprinting {
+        return;
+    }
+    for entity in &guns {
+        commands.entity(entity).remove::<Reloading>();
+    }
+}
+
+fn finish_reload(
+    mut commands: Commands,
+    mut guns: Query<(Entity, &Gun, &mut Ammo, &mut Reloading)>,
+    weapons: Res<Assets<WeaponDef>>,
+    time: Res<Time>,
+) {
+    for (entity, gun, mut ammo, mut reloading) in &mut guns {
+        if !reloading.0.tick(time.delta()).finished() {
+            continue;
+        }
+        commands.entity(entity).remove::<Reloading>();
+        let Some(weapon) = weapons.get(&gun.weapon) else {
+            continue;
+        };
+        let rounds =
+            (weapon.magazine_size - ammo.magazine.min(weapon.magazine_size)).min(ammo.reserve);
+        ammo.magazine += rounds;
+        ammo.reserve -= rounds;
+    }
+}
+
+fn update_ammo_text(
+    mut ammo_text: Single<&mut TextSpan, With<AmmoText>>,
+    gun: Single<(&Gun, Option<&Ammo>, Option<&Reloading>)>,
+    weapons: Res<Assets<WeaponDef>>,
+    mut localizer: Localizer,
+) {
+    let (gun, ammo, reloading) = *gun;
+    let name = weapons
+        .get(&gun.weapon)
+        .map_or("", |weapon| weapon.name.as_str());
+    let text = match (ammo, reloading) {
+        (_, Some(reloading)) => {
+            let reloading = Localized::new("hud.reloading")
+                .with_arg("weapon", Localized::verbatim(name))
+                .with_arg(
+                    "percent",
+                    Localized::verbatim(format!("{:.0}", reloading.0.fraction() * 100.)),
+                );
+            let Some(text) = localizer.text(&reloading) else {
+                return;
+            };
+            text
+        }
+        (Some(ammo), None) => format!("{name}  {} / {}", ammo.magazine, ammo.reserve),
+        (None, None) => String::new(),
+    };
+    if ammo_text.0 != text {
+        ammo_text.0 = text;
+    }
+}
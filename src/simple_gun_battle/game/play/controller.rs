@@ -0,0 +1,251 @@
+use avian3d::prelude::*;
+use bevy::prelude::*;
+
+use crate::simple_gun_battle::game::GameState;
+
+const SKIN_WIDTH: f32 = 0.02;
+const MAX_SLIDES: usize = 4;
+const GROUND_CHECK_DISTANCE: f32 = 0.05;
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(
+        FixedUpdate,
+        move_characters.run_if(in_state(GameState::Play)),
+    );
+}
+
+#[derive(Component, Debug, Clone)]
+pub struct CharacterController {
+    pub max_speed: f32,
+    pub speed_scale: f32,
+    pub ground_acceleration: f32,
+    pub air_acceleration: f32,
+    pub jump_speed: f32,
+    pub max_slope: f32,
+    pub step_height: f32,
+    pub snap_distance: f32,
+    pub movement: Vec3,
+    pub jump_buffer: f32,
+    pub velocity: Vec3,
+    pub grounded: bool,
+    pub ground_normal: Vec3,
+}
+
+impl Default for CharacterController {
+    fn default() -> Self {
+        Self {
+            max_speed: 14.,
+            speed_scale: 1.,
+            ground_acceleration: 100.,
+            air_acceleration: 20.,
+            jump_speed: 8.,
+            max_slope: 45_f32.to_radians(),
+            step_height: 0.4,
+            snap_distance: 0.3,
+            movement: Vec3::ZERO,
+            jump_buffer: 0.,
+            velocity: Vec3::ZERO,
+            grounded: false,
+            ground_normal: Vec3::Y,
+        }
+    }
+}
+
+impl CharacterController {
+    fn is_walkable(&self, normal: Vec3) -> bool {
+        normal.angle_between(Vec3::Y) <= self.max_slope
+    }
+}
+
+struct Sweep<'a, 'w, 's> {
+    spatial_query: &'a SpatialQuery<'w, 's>,
+    collider: &'a Collider,
+    rotation: Quat,
+    filter: SpatialQueryFilter,
+}
+
+impl Sweep<'_, '_, '_> {
+    fn hit(&self, origin: Vec3, motion: Vec3) -> Option<(f32, ShapeHitData)> {
+        let (direction, distance) = Dir3::new_and_length(motion).ok()?;
+        let hit = self.spatial_query.cast_shape(
+            self.collider,
+            origin,
+            self.rotation,
+            direction,
+            &ShapeCastConfig::from_max_distance(distance + SKIN_WIDTH),
+            &self.filter,
+        )?;
+        Some(((hit.distance - SKIN_WIDTH).clamp(0., distance), hit))
+    }
+
+    fn cast(&self, origin: Vec3, motion: Vec3) -> Option<(f32, Vec3)> {
+        self.hit(origin, motion)
+            .map(|(travel, hit)| (travel, hit.normal1))
+    }
+
+    fn ground(
+        &self,
+        origin: Vec3,
+        distance: f32,
+        controller: &CharacterController,
+    ) -> Option<(f32, Vec3)> {
+        let (travel, hit) = self.hit(origin, Vec3::NEG_Y * distance)?;
+        if controller.is_walkable(hit.normal1) {
+            return Some((travel, hit.normal1));
+        }
+        // A capsule resting on an edge reports the rounded normal of the corner,
+        // so judge the face just inside the contact instead.
+        let inward = -hit.normal1.with_y(0.).normalize_or_zero();
+        let surface = self.spatial_query.cast_ray(
+            hit.point1 + inward * SKIN_WIDTH + Vec3::Y * SKIN_WIDTH,
+            Dir3::NEG_Y,
+            SKIN_WIDTH * 2.,
+            true,
+            &self.filter,
+        )?;
+        controller
+            .is_walkable(surface.normal)
+            .then_some((travel, surface.normal))
+    }
+
+    fn slide(
+        &self,
+        mut position: Vec3,
+        mut motion: Vec3,
+        controller: &mut CharacterController,
+    ) -> Vec3 {
+        for _ in 0..MAX_SLIDES {
+            if motion.length_squared() < f32::EPSILON {
+                break;
+            }
+            let Some((travel, normal)) = self.cast(position, motion) else {
+                position += motion;
+                break;
+            };
+            let direction = motion.normalize();
+            position += direction * travel;
+            motion -= direction * travel;
+
+            let walkable = controller.is_walkable(normal);
+            if !walkable && controller.grounded {
+                if let Some(stepped) = self.step_up(position, motion.with_y(0.), controller) {
+                    position = stepped;
+                    motion = Vec3::ZERO;
+                    continue;
+                }
+            }
+            let normal = if !walkable && controller.grounded {
+                normal.with_y(0.).try_normalize().unwrap_or(normal)
+            } else {
+                normal
+            };
+            motion = motion.reject_from_normalized(normal);
+            // Grounded velocity stays horizontal; keeping the climb it picks up
+            // from a walkable slope would launch the character off its crest.
+            if !(walkable && controller.grounded) && controller.velocity.dot(normal) < 0. {
+                controller.velocity = controller.velocity.reject_from_normalized(normal);
+            }
+        }
+        position
+    }
+
+    fn step_up(
+        &self,
+        origin: Vec3,
+        motion: Vec3,
+        controller: &CharacterController,
+    ) -> Option<Vec3> {
+        let rise = Vec3::Y * controller.step_height;
+        let raised = origin
+            + self
+                .cast(origin, rise)
+                .map_or(rise, |(travel, _)| Vec3::Y * travel);
+        let advanced = raised
+            + self
+                .cast(raised, motion)
+                .map_or(motion, |(travel, _)| motion.normalize_or_zero() * travel);
+        if (advanced - raised).length_squared() < SKIN_WIDTH * SKIN_WIDTH {
+            return None;
+        }
+        let (drop, _) = self.ground(advanced, raised.y - origin.y + SKIN_WIDTH, controller)?;
+        let landed = advanced - Vec3::Y * drop;
+        (landed.y > origin.y + SKIN_WIDTH).then_some(landed)
+    }
+}
+
+pub fn move_characters(
+    mut characters: Query<(Entity, &mut CharacterController, &mut Transform, &Collider)>,
+    spatial_query: SpatialQuery,
+    gravity: Res<Gravity>,
+    time: Res<Time>,
+) {
+    let delta_secs = time.delta_secs();
+    if delta_secs <= 0. {
+        return;
+    }
+    for (entity, mut controller, mut transform, collider) in &mut characters {
+        let controller = &mut *controller;
+        let sweep = Sweep {
+            spatial_query: &spatial_query,
+            collider,
+            rotation: transform.rotation,
+            filter: SpatialQueryFilter::from_excluded_entities([entity]),
+        };
+
+        let acceleration = if controller.grounded {
+            controller.ground_acceleration
+        } else {
+            controller.air_acceleration
+        };
+        let wish = controller.movement.with_y(0.).clamp_length_max(1.)
+            * controller.max_speed
+            * controller.speed_scale;
+        let horizontal = controller.velocity.with_y(0.);
+        let horizontal =
+            horizontal + (wish - horizontal).clamp_length_max(acceleration * delta_secs);
+
+        let jumped = controller.grounded && controller.jump_buffer > 0.;
+        let vertical = if jumped {
+            controller.jump_speed
+        } else if controller.grounded {
+            0.
+        } else {
+            controller.velocity.y + gravity.0.y * delta_secs
+        };
+        controller.jump_buffer = if jumped {
+            0.
+        } else {
+            (controller.jump_buffer - delta_secs).max(0.)
+        };
+        controller.velocity = horizontal.with_y(vertical);
+
+        let mut motion = controller.velocity * delta_secs;
+        if controller.grounded && !jumped {
+            motion = motion.reject_from_normalized(controller.ground_normal);
+        }
+        let was_grounded = controller.grounded && !jumped;
+        if jumped {
+            controller.grounded = false;
+        }
+        let mut position = sweep.slide(transform.translation, motion, controller);
+
+        let probe = if was_grounded {
+            controller.snap_distance
+        } else {
+            GROUND_CHECK_DISTANCE
+        };
+        controller.grounded = false;
+        if controller.velocity.y <= 0. {
+            if let Some((drop, normal)) = sweep.ground(position, probe, controller) {
+                position.y -= drop;
+                controller.grounded = true;
+                controller.ground_normal = normal;
+                controller.velocity.y = 0.;
+            }
+        }
+        if !controller.grounded {
+            controller.ground_normal = Vec3::Y;
+        }
+        transform.translation = position;
+    }
+}
@@ -0,0 +1,187 @@
+use avian3d::prelude::*;
+use bevy::{prelude::*, utils::HashMap};
+use serde::Deserialize;
+
+use crate::simple_gun_battle::game::{spawn::Player, GameState, HealthPoints};
+
+use super::{
+    controller::CharacterController,
+    movement::{contact_collider, Crouched},
+    Enemy,
+};
+
+const FALL_DAMAGE_SPEED: f32 = 20.;
+const FALL_DAMAGE_PER_SPEED: f32 = 5.;
+const KILL_HEIGHT: f32 = -50.;
+const ENEMY_CONTACT_DAMAGE_PER_SECOND: f32 = 15.;
+// Wider than the character controller's skin, which keeps the player from
+// ever quite touching what it walks into.
+const CONTACT_MARGIN: f32 = 0.1;
+
+pub fn plugin(app: &mut App) {
+    app.register_required_components::<Player, FallVelocity>()
+        .add_observer(apply_damage)
+        .add_observer(player_died)
+        .add_systems(
+            FixedUpdate,
+            (
+                fall_damage.never_param_warn(),
+                contact_damage.never_param_warn(),
+                fall_out_of_world.never_param_warn(),
+            )
+                .run_if(in_state(GameState::Play)),
+        );
+}
+
+#[derive(Component)]
+pub struct Hazard {
+    pub damage_per_second: f32,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DamageKind {
+    Ballistic,
+    Melee,
+    Hazard,
+    Fall,
+    OutOfWorld,
+}
+
+#[derive(Component, Default)]
+pub struct Resistances(pub HashMap<DamageKind, f32>);
+
+impl Resistances {
+    fn multiplier(&self, kind: DamageKind) -> f32 {
+        1. - self.0.get(&kind).copied().unwrap_or(0.).clamp(0., 1.)
+    }
+}
+
+#[derive(Event, Clone, Copy)]
+pub struct DamageEvent {
+    pub target: Entity,
+    pub source: Option<Entity>,
+    pub amount: f32,
+    pub kind: DamageKind,
+    pub hit_point: Option<Vec3>,
+}
+
+#[derive(Event)]
+pub struct Damaged;
+
+#[derive(Event)]
+pub struct Died;
+
+#[derive(Component, Default)]
+struct FallVelocity(f32);
+
+fn apply_damage(
+    trigger: Trigger<DamageEvent>,
+    mut commands: Commands,
+    mut targets: Query<(&mut HealthPoints, Option<&Resistances>)>,
+    enemies: Query<(), With<Enemy>>,
+) {
+    let DamageEvent {
+        target,
+        source,
+        amount,
+        kind,
+        hit_point,
+    } = *trigger.event();
+    if source.is_some_and(|source| enemies.contains(source)) && enemies.contains(target) {
+        return;
+    }
+    let Ok((mut health_points, resistances)) = targets.get_mut(target) else {
+        return;
+    };
+    if health_points.current <= 0. {
+        return;
+    }
+    let amount = amount * resistances.map_or(1., |resistances| resistances.multiplier(kind));
+    if amount <= 0. {
+        return;
+    }
+    debug!("{target} took {amount:.1} {kind:?} damage at {hit_point:?}");
+    health_points.current = (health_points.current - amount).max(0.);
+    commands.trigger_targets(Damaged, target);
+    if health_points.current <= 0. {
+        commands.trigger_targets(Died, target);
+    }
+}
+
+fn player_died(
+    trigger: Trigger<Died>,
+    players: Query<(), With<Player>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if players.contains(trigger.entity()) {
+        next_state.set(GameState::GameOver);
+    }
+}
+
+fn fall_damage(
+    mut commands: Commands,
+    player: Single<(Entity, &Position, &CharacterController, &mut FallVelocity), With<Player>>,
+) {
+    let (entity, position, controller, mut fall_velocity) = player.into_inner();
+    let landed = controller.velocity.y > fall_velocity.0 * 0.5;
+    let impact_speed = -fall_velocity.0;
+    if landed && impact_speed > FALL_DAMAGE_SPEED {
+        commands.trigger(DamageEvent {
+            target: entity,
+            source: None,
+            amount: (impact_speed - FALL_DAMAGE_SPEED) * FALL_DAMAGE_PER_SPEED,
+            kind: DamageKind::Fall,
+            hit_point: Some(position.0),
+        });
+    }
+    fall_velocity.0 = controller.velocity.y;
+}
+
+fn contact_damage(
+    mut commands: Commands,
+    player: Single<(Entity, &Position, &Rotation, Has<Crouched>), With<Player>>,
+    enemies: Query<(), With<Enemy>>,
+    hazards: Query<&Hazard>,
+    spatial_query: SpatialQuery,
+    time: Res<Time>,
+) {
+    let (player, position, rotation, crouched) = *player;
+    let touching = spatial_query.shape_intersections(
+        &contact_collider(crouched, CONTACT_MARGIN),
+        position.0,
+        rotation.0,
+        &SpatialQueryFilter::from_excluded_entities([player]),
+    );
+    for other in touching {
+        let (damage_per_second, kind) = if enemies.contains(other) {
+            (ENEMY_CONTACT_DAMAGE_PER_SECOND, DamageKind::Melee)
+        } else if let Ok(hazard) = hazards.get(other) {
+            (hazard.damage_per_second, DamageKind::Hazard)
+        } else {
+            continue;
+        };
+        commands.trigger(DamageEvent {
+            target: player,
+            source: Some(other),
+            amount: damage_per_second * time.delta_secs(),
+            kind,
+            hit_point: None,
+        });
+    }
+}
+
+fn fall_out_of_world(
+    mut commands: Commands,
+    player: Single<(Entity, &Transform, &HealthPoints), With<Player>>,
+) {
+    let (entity, transform, health_points) = player.into_inner();
+    if transform.translation.y < KILL_HEIGHT {
+        commands.trigger(DamageEvent {
+            target: entity,
+            source: None,
+            amount: health_points.current,
+            kind: DamageKind::OutOfWorld,
+            hit_point: Some(transform.translation),
+        });
+    }
+}
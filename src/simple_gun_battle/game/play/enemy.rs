@@ -0,0 +1,224 @@
+use std::ops::Range;
+
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::simple_gun_battle::game::{
+    navigation::NavAgent, rng::GameRng, spawn::Player, GameState,
+};
+
+use super::{movement::MovementState, Enemy, FireBullet};
+
+const EYE_HEIGHT: f32 = 0.5;
+const VIEW_DISTANCE: f32 = 60.;
+const VIEW_CONE_COS: f32 = 0.5;
+const REACTION_SECONDS: f32 = 0.6;
+const SEARCH_SECONDS: f32 = 6.;
+const FIRE_INTERVAL_SECONDS: f32 = 1.2;
+const FIRE_SPREAD_DEGREES: f32 = 3.;
+const BULLET_SPEED: f32 = 50.;
+const BULLET_DAMAGE: Range<f32> = 5.0..10.0;
+const TURN_SPEED: f32 = 4.;
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(
+        FixedUpdate,
+        (
+            perceive_player.never_param_warn(),
+            update_enemy_state,
+            search_last_seen,
+            face_target.never_param_warn(),
+            enemy_fire,
+        )
+            .chain()
+            .run_if(in_state(GameState::Play)),
+    );
+}
+
+#[derive(Component)]
+pub struct EnemyAi {
+    state: EnemyState,
+    sees_player: bool,
+    hears_player: bool,
+    last_seen: Option<Vec3>,
+    fire_cooldown: Timer,
+}
+
+impl Default for EnemyAi {
+    fn default() -> Self {
+        Self {
+            state: EnemyState::Idle,
+            sees_player: false,
+            hears_player: false,
+            last_seen: None,
+            fire_cooldown: Timer::from_seconds(FIRE_INTERVAL_SECONDS, TimerMode::Repeating),
+        }
+    }
+}
+
+impl EnemyAi {
+    pub fn is_idle(&self) -> bool {
+        self.state == EnemyState::Idle
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum EnemyState {
+    Idle,
+    Alert(Timer),
+    Attack,
+    Search(Timer),
+}
+
+fn perceive_player(
+    mut enemies: Query<(Entity, &Position, &Rotation, &mut EnemyAi), With<Enemy>>,
+    player: Single<(Entity, &GlobalTransform, &MovementState), With<Player>>,
+    spatial_query: SpatialQuery,
+) {
+    let (player, player_transform, movement_state) = *player;
+    let noise_radius = movement_state.noise_radius();
+    let target = player_transform.translation();
+    for (entity, position, rotation, mut ai) in &mut enemies {
+        let eye = position.0 + Vec3::Y * EYE_HEIGHT;
+        let forward = rotation.0 * Vec3::NEG_Z;
+        let offset = target - eye;
+        let distance = offset.length();
+        let Ok(direction) = Dir3::new(offset) else {
+            continue;
+        };
+        let in_view = distance <= VIEW_DISTANCE
+            && (ai.state != EnemyState::Idle || direction.dot(forward) >= VIEW_CONE_COS);
+        ai.sees_player = in_view
+            && spatial_query
+                .cast_ray(
+                    eye,
+                    direction,
+                    distance,
+                    true,
+                    &SpatialQueryFilter::from_excluded_entities([entity]),
+                )
+                .is_some_and(|hit| hit.entity == player);
+        ai.hears_player = !ai.sees_player && distance <= noise_radius;
+        if ai.sees_player || ai.hears_player {
+            ai.last_seen = Some(target);
+        }
+    }
+}
+
+fn update_enemy_state(mut enemies: Query<&mut EnemyAi, With<Enemy>>, time: Res<Time>) {
+    for mut ai in &mut enemies {
+        let sees_player = ai.sees_player;
+        let hears_player = ai.hears_player;
+        let next_state = match &mut ai.state {
+            EnemyState::Idle if sees_player => Some(EnemyState::Alert(Timer::from_seconds(
+                REACTION_SECONDS,
+                TimerMode::Once,
+            ))),
+            EnemyState::Idle => hears_player.then(search),
+            EnemyState::Alert(timer) => {
+                if !sees_player {
+                    Some(search())
+                } else if timer.tick(time.delta()).finished() {
+                    Some(EnemyState::Attack)
+                } else {
+                    None
+                }
+            }
+            EnemyState::Attack => (!sees_player).then(search),
+            EnemyState::Search(timer) => {
+                if sees_player {
+                    Some(EnemyState::Attack)
+                } else if timer.tick(time.delta()).finished() {
+                    Some(EnemyState::Idle)
+                } else {
+                    None
+                }
+            }
+        };
+        if let Some(next_state) = next_state {
+            if next_state == EnemyState::Idle {
+                ai.last_seen = None;
+            }
+            ai.state = next_state;
+        }
+    }
+}
+
+fn search() -> EnemyState {
+    EnemyState::Search(Timer::from_seconds(SEARCH_SECONDS, TimerMode::Once))
+}
+
+fn search_last_seen(mut enemies: Query<(&EnemyAi, &mut NavAgent), With<Enemy>>) {
+    for (ai, mut agent) in &mut enemies {
+        match ai.state {
+            EnemyState::Search(_) => agent.set_destination(ai.last_seen),
+            _ => agent.set_destination(None),
+        }
+    }
+}
+
+fn face_target(
+    mut enemies: Query<(&Position, &mut Rotation, &EnemyAi), With<Enemy>>,
+    time: Res<Time>,
+) {
+    for (position, mut rotation, ai) in &mut enemies {
+        if ai.state == EnemyState::Idle {
+            continue;
+        }
+        let Some(target) = ai.last_seen else {
+            continue;
+        };
+        let Ok(direction) = Dir3::new((target - position.0).with_y(0.)) else {
+            continue;
+        };
+        let target_rotation = Transform::default().looking_to(direction, Vec3::Y).rotation;
+        rotation.0 = rotation
+            .0
+            .slerp(target_rotation, (TURN_SPEED * time.delta_secs()).min(1.));
+    }
+}
+
+fn enemy_fire(
+    mut commands: Commands,
+    mut enemies: Query<(Entity, &Position, &mut EnemyAi), With<Enemy>>,
+    asset_server: Res<AssetServer>,
+    mut rng: ResMut<GameRng>,
+    time: Res<Time>,
+) {
+    for (entity, position, mut ai) in &mut enemies {
+        if ai.state != EnemyState::Attack || !ai.sees_player {
+            ai.fire_cooldown.reset();
+            continue;
+        }
+        if !ai.fire_cooldown.tick(time.delta()).just_finished() {
+            continue;
+        }
+        let Some(target) = ai.last_seen else {
+            continue;
+        };
+        let eye = position.0 + Vec3::Y * EYE_HEIGHT;
+        let Ok(direction) = Dir3::new(target - eye) else {
+            continue;
+        };
+        let spread = FIRE_SPREAD_DEGREES.to_radians();
+        let direction = Quat::from_euler(
+            EulerRot::YXZ,
+            rng.gen_range(-spread..spread),
+            rng.gen_range(-spread..spread),
+            0.,
+        ) * direction;
+        commands.trigger(FireBullet {
+            origin: eye + direction * 0.8,
+            direction,
+            speed: BULLET_SPEED,
+            damage: rng.gen_range(BULLET_DAMAGE),
+            shooter: entity,
+        });
+        commands.entity(entity).with_child((
+            AudioPlayer::<AudioSource>(asset_server.load("gunfire.mp3")),
+            PlaybackSettings::DESPAWN.with_spatial(true),
+            Transform::default(),
+        ));
+    }
+}
@@ -0,0 +1,260 @@
+use avian3d::prelude::*;
+use bevy::prelude::*;
+
+use crate::simple_gun_battle::game::{spawn::Player, GameState};
+
+use super::{
+    controller::{move_characters, CharacterController},
+    view::PlayerView,
+};
+
+const PLAYER_RADIUS: f32 = 0.5;
+const STANDING_HEIGHT: f32 = 1.5;
+const CROUCHING_HEIGHT: f32 = 0.5;
+const SPRINT_SPEED_SCALE: f32 = 1.6;
+const CROUCH_SPEED_SCALE: f32 = 0.45;
+const SLIDE_BOOST: f32 = 1.2;
+const SLIDE_DECELERATION: f32 = 14.;
+const STAMINA: f32 = 100.;
+const STAMINA_DRAIN_PER_SECOND: f32 = 25.;
+const STAMINA_REGEN_PER_SECOND: f32 = 15.;
+pub const STAMINA_RECOVERED: f32 = 25.;
+const EYE_EASE_SPEED: f32 = 4.;
+
+pub fn plugin(app: &mut App) {
+    app.register_required_components::<Player, MovementInput>()
+        .register_required_components::<Player, MovementState>()
+        .register_required_components::<Player, Stamina>()
+        .add_systems(
+            FixedUpdate,
+            update_movement
+                .never_param_warn()
+                .before(move_characters)
+                .run_if(in_state(GameState::Play)),
+        )
+        .add_systems(
+            Update,
+            ease_eye_height
+                .never_param_warn()
+                .run_if(in_state(GameState::Play)),
+        );
+}
+
+pub fn standing_collider() -> Collider {
+    Collider::capsule(PLAYER_RADIUS, STANDING_HEIGHT)
+}
+
+/// The player's current capsule grown by `margin` on every side.
+pub fn contact_collider(crouched: bool, margin: f32) -> Collider {
+    let height = if crouched {
+        CROUCHING_HEIGHT
+    } else {
+        STANDING_HEIGHT
+    };
+    Collider::capsule(PLAYER_RADIUS + margin, height)
+}
+
+#[derive(Component, Debug, Default)]
+pub struct MovementInput {
+    pub direction: Vec3,
+    pub sprint: bool,
+    pub crouch: bool,
+}
+
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum MovementState {
+    #[default]
+    Idle,
+    Walking,
+    Sprinting,
+    Crouching,
+    Sliding,
+    Airborne,
+}
+
+impl MovementState {
+    pub fn spread_scale(self) -> f32 {
+        match self {
+            MovementState::Idle => 1.,
+            MovementState::Walking => 1.25,
+            MovementState::Sprinting => 2.,
+            MovementState::Crouching => 0.6,
+            MovementState::Sliding => 1.75,
+            MovementState::Airborne => 2.5,
+        }
+    }
+
+    pub fn noise_radius(self) -> f32 {
+        match self {
+            MovementState::Idle | MovementState::Crouching => 0.,
+            MovementState::Walking => 8.,
+            MovementState::Sprinting => 20.,
+            MovementState::Sliding => 12.,
+            MovementState::Airborne => 4.,
+        }
+    }
+}
+
+#[derive(Component, Debug, Clone)]
+pub struct Stamina {
+    pub current: f32,
+    pub max: f32,
+    exhausted: bool,
+}
+
+impl Stamina {
+    pub fn is_exhausted(&self) -> bool {
+        self.exhausted
+    }
+}
+
+impl Default for Stamina {
+    fn default() -> Self {
+        Self {
+            current: STAMINA,
+            max: STAMINA,
+            exhausted: false,
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct Crouched;
+
+#[derive(Component)]
+struct Slide {
+    direction: Vec3,
+    speed: f32,
+}
+
+#[allow(clippy::type_complexity)]
+fn update_movement(
+    mut commands: Commands,
+    player: Single<
+        (
+            Entity,
+            &MovementInput,
+            &mut CharacterController,
+            &mut MovementState,
+            &mut Stamina,
+            &mut Transform,
+            Has<Crouched>,
+            Option<&mut Slide>,
+        ),
+        With<Player>,
+    >,
+    mut view: Single<&mut Transform, (With<PlayerView>, Without<Player>)>,
+    spatial_query: SpatialQuery,
+    time: Res<Time>,
+) {
+    let (entity, input, mut controller, mut state, mut stamina, mut transform, was_crouched, slide) =
+        player.into_inner();
+    let delta_secs = time.delta_secs();
+    let crouch_shift = (STANDING_HEIGHT - CROUCHING_HEIGHT) / 2.;
+
+    let mut crouched = was_crouched;
+    if input.crouch && !was_crouched {
+        transform.translation.y -= crouch_shift;
+        view.translation.y += crouch_shift;
+        commands
+            .entity(entity)
+            .insert((Collider::capsule(PLAYER_RADIUS, CROUCHING_HEIGHT), Crouched));
+        crouched = true;
+    } else if !input.crouch && was_crouched {
+        let headroom = spatial_query.shape_intersections(
+            &standing_collider(),
+            transform.translation + Vec3::Y * crouch_shift,
+            transform.rotation,
+            &SpatialQueryFilter::from_excluded_entities([entity]),
+        );
+        if headroom.is_empty() {
+            transform.translation.y += crouch_shift;
+            view.translation.y -= crouch_shift;
+            commands
+                .entity(entity)
+                .insert(standing_collider())
+                .remove::<Crouched>();
+            crouched = false;
+        }
+    }
+
+    let horizontal = controller.velocity.with_y(0.);
+    let crouch_speed = controller.max_speed * CROUCH_SPEED_SCALE;
+    let mut sliding = match slide {
+        Some(mut slide) => {
+            slide.speed -= SLIDE_DECELERATION * delta_secs;
+            let sliding = crouched && controller.grounded && slide.speed > crouch_speed;
+            if sliding {
+                controller.movement = slide.direction;
+                controller.speed_scale = slide.speed / controller.max_speed;
+            } else {
+                commands.entity(entity).remove::<Slide>();
+            }
+            sliding
+        }
+        None => false,
+    };
+    // Halfway between walking and sprinting speed, so only a sprint turns into a slide.
+    let slide_min_speed = controller.max_speed * (1. + SPRINT_SPEED_SCALE) / 2.;
+    if crouched && !was_crouched && controller.grounded && horizontal.length() >= slide_min_speed {
+        let speed = horizontal.length() * SLIDE_BOOST;
+        controller.movement = horizontal.normalize();
+        controller.speed_scale = speed / controller.max_speed;
+        commands.entity(entity).insert(Slide {
+            direction: horizontal.normalize(),
+            speed,
+        });
+        sliding = true;
+    }
+
+    let moving = input.direction != Vec3::ZERO;
+    let sprinting = input.sprint
+        && moving
+        && !crouched
+        && controller.grounded
+        && !stamina.exhausted
+        && stamina.current > 0.;
+    if sprinting {
+        stamina.current = (stamina.current - STAMINA_DRAIN_PER_SECOND * delta_secs).max(0.);
+        stamina.exhausted = stamina.current <= 0.;
+    } else {
+        stamina.current =
+            (stamina.current + STAMINA_REGEN_PER_SECOND * delta_secs).min(stamina.max);
+        if stamina.current >= STAMINA_RECOVERED {
+            stamina.exhausted = false;
+        }
+    }
+
+    if !sliding {
+        controller.movement = input.direction;
+        controller.speed_scale = if crouched {
+            CROUCH_SPEED_SCALE
+        } else if sprinting {
+            SPRINT_SPEED_SCALE
+        } else {
+            1.
+        };
+    }
+
+    state.set_if_neq(if !controller.grounded {
+        MovementState::Airborne
+    } else if sliding {
+        MovementState::Sliding
+    } else if crouched {
+        MovementState::Crouching
+    } else if sprinting {
+        MovementState::Sprinting
+    } else if moving {
+        MovementState::Walking
+    } else {
+        MovementState::Idle
+    });
+}
+
+fn ease_eye_height(mut view: Single<&mut Transform, With<PlayerView>>, time: Res<Time>) {
+    let offset = view.translation.y;
+    if offset != 0. {
+        let step = EYE_EASE_SPEED * time.delta_secs();
+        view.translation.y = offset - offset.clamp(-step, step);
+    }
+}
@@ -1,7 +1,8 @@
 use bevy::{color::palettes::tailwind, prelude::*};
 
-use crate::{
-    game::GameState,
+use crate::simple_gun_battle::{
+    game::{level::Levels, GameState, SceneIndex},
+    locale::Localized,
     ui_utils::{Widgets, DEFAULT_FONT},
 };
 
@@ -15,7 +16,18 @@ pub fn plugin(app: &mut App) {
 #[derive(Component)]
 pub struct EnemyCountText;
 
-fn spawn_enemy_count_text(mut commands: Commands) {
+#[derive(Component)]
+pub struct PlayerHealthText;
+
+#[derive(Component)]
+pub struct AmmoText;
+
+fn spawn_enemy_count_text(mut commands: Commands, scene_index: Res<SceneIndex>, levels: Levels) {
+    let level_name = levels
+        .get(scene_index.0)
+        .map_or_else(Localized::default, |level| {
+            Localized::new(level.name.clone())
+        });
     commands
         .spawn((
             Node {
@@ -31,10 +43,11 @@ fn spawn_enemy_count_text(mut commands: Commands) {
             parent
                 .spawn((
                     Node {
+                        flex_direction: FlexDirection::Column,
                         justify_content: JustifyContent::Center,
                         align_items: AlignItems::Center,
                         width: Val::Percent(25.),
-                        height: Val::Percent(10.),
+                        height: Val::Percent(20.),
                         ..default()
                     },
                     BackgroundColor(Color::BLACK.with_alpha(0.5)),
@@ -47,16 +60,40 @@ fn spawn_enemy_count_text(mut commands: Commands) {
                     };
                     parent
                         .spawn((
-                            Text::new("敌人总数："),
+                            Localized::new("hud.enemy_count").with_arg("level", level_name),
                             text_font.clone(),
                             TextColor(tailwind::BLUE_600.into()),
                         ))
                         .with_child((
                             TextSpan::new("0"),
-                            text_font,
+                            text_font.clone(),
                             TextColor(tailwind::RED_600.into()),
                             EnemyCountText,
                         ));
+                    parent
+                        .spawn((
+                            Localized::new("hud.health"),
+                            text_font.clone(),
+                            TextColor(tailwind::BLUE_600.into()),
+                        ))
+                        .with_child((
+                            TextSpan::new("0"),
+                            text_font.clone(),
+                            TextColor(tailwind::GREEN_600.into()),
+                            PlayerHealthText,
+                        ));
+                    parent
+                        .spawn((
+                            Localized::new("hud.ammo"),
+                            text_font.clone(),
+                            TextColor(tailwind::BLUE_600.into()),
+                        ))
+                        .with_child((
+                            TextSpan::default(),
+                            text_font,
+                            TextColor(tailwind::AMBER_500.into()),
+                            AmmoText,
+                        ));
                 });
         });
 }
@@ -0,0 +1,93 @@
+use bevy::{input::mouse::MouseMotion, prelude::*};
+
+use crate::simple_gun_battle::{
+    game::{
+        spawn::{Gun, Player},
+        GameState,
+    },
+    input_map::ActionInput,
+    settings::Settings,
+};
+
+const MOUSE_RADIANS_PER_PIXEL: f32 = 1. / 400.;
+const GAMEPAD_YAW_SPEED: f32 = 4.;
+const GAMEPAD_PITCH_SPEED: f32 = 2.;
+const SWAY_PER_RADIAN_PER_SECOND: f32 = 0.02;
+const MAX_SWAY_DEGREES: f32 = 4.;
+const SWAY_SMOOTHING: f32 = 10.;
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (look.never_param_warn(), sway_gun.never_param_warn())
+            .chain()
+            .run_if(in_state(GameState::Play)),
+    );
+}
+
+#[derive(Component, Debug)]
+pub struct PlayerView {
+    pub pitch: f32,
+    pub max_pitch: f32,
+    look_delta: Vec2,
+}
+
+impl PlayerView {
+    pub fn new(max_pitch_degrees: f32) -> Self {
+        Self {
+            pitch: 0.,
+            max_pitch: max_pitch_degrees.to_radians(),
+            look_delta: Vec2::ZERO,
+        }
+    }
+}
+
+fn look(
+    mut player: Single<&mut Transform, With<Player>>,
+    view: Single<(&mut PlayerView, &mut Transform), Without<Player>>,
+    mut mouse_reader: EventReader<MouseMotion>,
+    actions: ActionInput,
+    settings: Res<Settings>,
+    time: Res<Time>,
+) {
+    let (mut view, mut view_transform) = view.into_inner();
+    let sensitivity = settings.mouse_sensitivity;
+    let pitch_sign = if settings.invert_y { -1. } else { 1. };
+
+    let mouse = mouse_reader
+        .read()
+        .map(|MouseMotion { delta }| *delta)
+        .sum::<Vec2>()
+        * MOUSE_RADIANS_PER_PIXEL;
+    let aim = actions.aim() * time.delta_secs();
+    let yaw = -(mouse.x + aim.x * GAMEPAD_YAW_SPEED) * sensitivity;
+    let pitch = (mouse.y - aim.y * GAMEPAD_PITCH_SPEED) * sensitivity * pitch_sign;
+
+    if yaw != 0. {
+        player.rotate_y(yaw);
+    }
+    let clamped = (view.pitch + pitch).clamp(-view.max_pitch, view.max_pitch);
+    view.look_delta = Vec2::new(yaw, clamped - view.pitch);
+    if clamped != view.pitch {
+        view.pitch = clamped;
+        view_transform.rotation = Quat::from_rotation_x(clamped);
+    }
+}
+
+fn sway_gun(
+    mut gun: Single<&mut Transform, With<Gun>>,
+    view: Single<&PlayerView>,
+    time: Res<Time>,
+) {
+    let delta_secs = time.delta_secs();
+    if delta_secs <= 0. {
+        return;
+    }
+    let max_sway = MAX_SWAY_DEGREES.to_radians();
+    let sway = (-view.look_delta / delta_secs * SWAY_PER_RADIAN_PER_SECOND)
+        .clamp(Vec2::splat(-max_sway), Vec2::splat(max_sway));
+    let target = Quat::from_euler(EulerRot::YXZ, sway.x, sway.y, 0.);
+    gun.rotation = gun
+        .rotation
+        .slerp(target, (SWAY_SMOOTHING * delta_secs).min(1.));
+}
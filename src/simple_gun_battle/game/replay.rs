@@ -0,0 +1,465 @@
+use std::{hash::Hash, mem, path::PathBuf, time::Duration};
+
+use bevy::{
+    input::{
+        mouse::{MouseMotion, MouseScrollUnit, MouseWheel},
+        InputSystem,
+    },
+    prelude::*,
+    time::{TimeSystem, TimeUpdateStrategy},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::simple_gun_battle::{headless::Headless, input_map::ActionInputSystem, AppState};
+
+use super::{
+    inventory::{Inventory, InventorySnapshot, LoadInventory},
+    level::Levels,
+    rng::RngSeed,
+    EnemyCount, GameState, SceneIndex,
+};
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(Startup, load_replay.run_if(resource_exists::<ReplayInput>))
+        .add_systems(OnExit(GameState::Spawn), discard_fixed_overstep)
+        .add_systems(
+            OnExit(GameState::Spawn),
+            start_recording.run_if(resource_exists::<RecordInput>),
+        )
+        .add_systems(
+            OnEnter(GameState::NextLevel),
+            (finish_recording, finish_replay),
+        )
+        .add_systems(
+            OnEnter(GameState::GameOver),
+            (finish_recording, finish_replay),
+        )
+        .add_systems(
+            OnEnter(GameState::Victory),
+            (finish_recording, finish_replay),
+        )
+        .add_systems(OnEnter(GameState::Menu), resume_replay)
+        .add_systems(OnExit(AppState::Game), abandon_recording)
+        .add_systems(
+            Update,
+            start_replay.run_if(in_state(AppState::StartMenu).and(resource_exists::<Replay>)),
+        )
+        .add_systems(
+            First,
+            replay_time
+                .before(TimeSystem)
+                .run_if(resource_exists::<Replay>),
+        )
+        .add_systems(
+            PreUpdate,
+            replay_input
+                .after(InputSystem)
+                .before(ActionInputSystem)
+                .run_if(resource_exists::<Replay>),
+        )
+        .add_systems(
+            Last,
+            exit_finished_replay.run_if(resource_exists::<Headless>.and(resource_exists::<Replay>)),
+        )
+        .add_systems(
+            FixedFirst,
+            record_tick.run_if(in_state(GameState::Play).and(resource_exists::<ActiveRecording>)),
+        )
+        .add_systems(
+            Last,
+            (
+                record_input.run_if(in_state(GameState::Play)),
+                save_recording_on_exit,
+            )
+                .chain()
+                .run_if(resource_exists::<ActiveRecording>),
+        );
+}
+
+#[derive(Resource)]
+pub struct RecordInput(pub PathBuf);
+
+#[derive(Resource)]
+pub struct ReplayInput(pub PathBuf);
+
+#[derive(Serialize, Deserialize)]
+struct Recording {
+    seed: u64,
+    scene_index: usize,
+    inventory: InventorySnapshot,
+    ticks: Vec<InputTick>,
+    outcome: Option<Outcome>,
+}
+
+/// Input gathered between two fixed ticks, in the order the ticks ran. The
+/// first entry holds the input from before the first tick.
+#[derive(Serialize, Deserialize)]
+struct InputTick {
+    keys: RecordedButtons<KeyCode>,
+    mouse_buttons: RecordedButtons<MouseButton>,
+    mouse_motion: Vec<Vec2>,
+    mouse_wheel: Vec<Vec2>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RecordedButtons<T> {
+    pressed: Vec<T>,
+    just_pressed: Vec<T>,
+    just_released: Vec<T>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Outcome {
+    state: GameState,
+    enemy_count: usize,
+}
+
+#[derive(Resource)]
+struct ActiveRecording {
+    path: PathBuf,
+    recording: Recording,
+    pending: InputTick,
+}
+
+#[derive(Resource)]
+struct Replay {
+    recording: Recording,
+    started: bool,
+    cursor: usize,
+    finished: bool,
+    reproduced: bool,
+}
+
+impl InputTick {
+    fn held(keys: Vec<KeyCode>, mouse_buttons: Vec<MouseButton>) -> Self {
+        Self {
+            keys: RecordedButtons::held(keys),
+            mouse_buttons: RecordedButtons::held(mouse_buttons),
+            mouse_motion: Vec::new(),
+            mouse_wheel: Vec::new(),
+        }
+    }
+}
+
+impl ActiveRecording {
+    fn flush(&mut self) {
+        let held = InputTick::held(
+            self.pending.keys.pressed.clone(),
+            self.pending.mouse_buttons.pressed.clone(),
+        );
+        let tick = mem::replace(&mut self.pending, held);
+        self.recording.ticks.push(tick);
+    }
+}
+
+impl<T: Copy + Eq + Hash + Send + Sync + 'static> RecordedButtons<T> {
+    fn held(pressed: Vec<T>) -> Self {
+        Self {
+            pressed,
+            just_pressed: Vec::new(),
+            just_released: Vec::new(),
+        }
+    }
+
+    fn accumulate(&mut self, input: &ButtonInput<T>) {
+        self.pressed = input.get_pressed().copied().collect();
+        for &button in input.get_just_pressed() {
+            if !self.just_pressed.contains(&button) {
+                self.just_pressed.push(button);
+            }
+        }
+        for &button in input.get_just_released() {
+            if !self.just_released.contains(&button) {
+                self.just_released.push(button);
+            }
+        }
+    }
+
+    fn apply(&self, input: &mut ButtonInput<T>) {
+        input.reset_all();
+        for &button in &self.just_released {
+            input.press(button);
+            input.release(button);
+            input.clear_just_pressed(button);
+        }
+        for &button in &self.pressed {
+            input.press(button);
+            if !self.just_pressed.contains(&button) {
+                input.clear_just_pressed(button);
+            }
+        }
+        for &button in &self.just_pressed {
+            if !self.pressed.contains(&button) {
+                input.press(button);
+                input.release(button);
+            }
+        }
+    }
+}
+
+fn discard_fixed_overstep(mut time: ResMut<Time<Fixed>>) {
+    let overstep = time.overstep();
+    time.discard_overstep(overstep);
+}
+
+fn start_recording(
+    mut commands: Commands,
+    record_input: Res<RecordInput>,
+    seed: Res<RngSeed>,
+    scene_index: Res<SceneIndex>,
+    inventory: Res<Inventory>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mouse_button: Res<ButtonInput<MouseButton>>,
+) {
+    commands.insert_resource(ActiveRecording {
+        path: record_input.0.clone(),
+        recording: Recording {
+            seed: seed.0,
+            scene_index: scene_index.0,
+            inventory: inventory.snapshot(),
+            ticks: Vec::new(),
+            outcome: None,
+        },
+        pending: InputTick::held(
+            keyboard.get_pressed().copied().collect(),
+            mouse_button.get_pressed().copied().collect(),
+        ),
+    });
+}
+
+fn record_input(
+    mut active_recording: ResMut<ActiveRecording>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mouse_button: Res<ButtonInput<MouseButton>>,
+    mut motion_reader: EventReader<MouseMotion>,
+    mut wheel_reader: EventReader<MouseWheel>,
+) {
+    let pending = &mut active_recording.pending;
+    pending.keys.accumulate(&keyboard);
+    pending.mouse_buttons.accumulate(&mouse_button);
+    pending
+        .mouse_motion
+        .extend(motion_reader.read().map(|motion| motion.delta));
+    pending
+        .mouse_wheel
+        .extend(wheel_reader.read().map(|wheel| Vec2::new(wheel.x, wheel.y)));
+}
+
+fn record_tick(mut active_recording: ResMut<ActiveRecording>) {
+    active_recording.flush();
+}
+
+fn finish_recording(
+    mut commands: Commands,
+    active_recording: Option<ResMut<ActiveRecording>>,
+    state: Res<State<GameState>>,
+    enemy_count: Res<EnemyCount>,
+) {
+    let Some(mut active_recording) = active_recording else {
+        return;
+    };
+    active_recording.flush();
+    active_recording.recording.outcome = Some(Outcome {
+        state: *state.get(),
+        enemy_count: enemy_count.0,
+    });
+    save_recording(&active_recording);
+    commands.remove_resource::<ActiveRecording>();
+}
+
+fn abandon_recording(mut commands: Commands, active_recording: Option<ResMut<ActiveRecording>>) {
+    if let Some(mut active_recording) = active_recording {
+        active_recording.flush();
+        save_recording(&active_recording);
+        commands.remove_resource::<ActiveRecording>();
+    }
+}
+
+fn save_recording_on_exit(
+    mut active_recording: ResMut<ActiveRecording>,
+    mut exit_reader: EventReader<AppExit>,
+) {
+    if exit_reader.read().next().is_some() {
+        active_recording.flush();
+        save_recording(&active_recording);
+    }
+}
+
+fn save_recording(active_recording: &ActiveRecording) {
+    let path = &active_recording.path;
+    let result = ron::ser::to_string(&active_recording.recording)
+        .map_err(|error| error.to_string())
+        .and_then(|text| std::fs::write(path, text).map_err(|error| error.to_string()));
+    match result {
+        Ok(()) => info!(
+            "saved {} input ticks to {}",
+            active_recording.recording.ticks.len(),
+            path.display()
+        ),
+        Err(error) => error!(
+            "could not save input recording to {}: {error}",
+            path.display()
+        ),
+    }
+}
+
+fn load_replay(mut commands: Commands, replay_input: Res<ReplayInput>) {
+    let path = &replay_input.0;
+    let result = std::fs::read_to_string(path)
+        .map_err(|error| error.to_string())
+        .and_then(|text| ron::from_str::<Recording>(&text).map_err(|error| error.to_string()));
+    match result {
+        Ok(recording) => {
+            info!(
+                "replaying {} input ticks from {}",
+                recording.ticks.len(),
+                path.display()
+            );
+            commands.insert_resource(RngSeed(recording.seed));
+            commands.insert_resource(Replay {
+                recording,
+                started: false,
+                cursor: 0,
+                finished: false,
+                reproduced: false,
+            });
+        }
+        Err(error) => error!("could not load input recording {}: {error}", path.display()),
+    }
+}
+
+fn start_replay(
+    mut commands: Commands,
+    mut replay: ResMut<Replay>,
+    levels: Levels,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    if replay.started || !levels.is_loaded() {
+        return;
+    }
+    replay.started = true;
+    commands.insert_resource(SceneIndex(replay.recording.scene_index));
+    commands.trigger(LoadInventory(replay.recording.inventory.clone()));
+    next_state.set(AppState::Game);
+}
+
+fn plays_this_frame(
+    state: Option<Res<State<GameState>>>,
+    next_state: Option<Res<NextState<GameState>>>,
+) -> bool {
+    match next_state.as_deref() {
+        Some(NextState::Pending(next_state)) => *next_state == GameState::Play,
+        _ => state.is_some_and(|state| *state.get() == GameState::Play),
+    }
+}
+
+/// Runs exactly one fixed tick per replayed input, except for the input that
+/// was gathered before the first tick and the frame that finds the input
+/// exhausted.
+fn replay_time(
+    replay: Res<Replay>,
+    state: Option<Res<State<GameState>>>,
+    next_state: Option<Res<NextState<GameState>>>,
+    fixed_time: Res<Time<Fixed>>,
+    mut time_update_strategy: ResMut<TimeUpdateStrategy>,
+) {
+    let replaying = replay.started && !replay.finished && plays_this_frame(state, next_state);
+    let ticks = replay.recording.ticks.len();
+    *time_update_strategy = match replay.cursor {
+        _ if !replaying => TimeUpdateStrategy::Automatic,
+        cursor if cursor == 0 || cursor >= ticks => {
+            TimeUpdateStrategy::ManualDuration(Duration::ZERO)
+        }
+        _ => TimeUpdateStrategy::ManualDuration(fixed_time.timestep()),
+    };
+}
+
+#[allow(clippy::too_many_arguments)]
+fn replay_input(
+    mut replay: ResMut<Replay>,
+    state: Option<Res<State<GameState>>>,
+    next_state: Option<Res<NextState<GameState>>>,
+    mut keyboard: ResMut<ButtonInput<KeyCode>>,
+    mut mouse_button: ResMut<ButtonInput<MouseButton>>,
+    mut motion_events: ResMut<Events<MouseMotion>>,
+    mut wheel_events: ResMut<Events<MouseWheel>>,
+    enemy_count: Option<Res<EnemyCount>>,
+) {
+    if !replay.started || replay.finished || !plays_this_frame(state, next_state) {
+        return;
+    }
+    let Some(tick) = replay.recording.ticks.get(replay.cursor) else {
+        replay.finished = true;
+        replay.reproduced = replay.recording.outcome.is_none();
+        let enemy_count = enemy_count.map_or(0, |enemy_count| enemy_count.0);
+        match &replay.recording.outcome {
+            Some(outcome) => warn!(
+                "replay ran out of input while still playing with {enemy_count} enemies, \
+                 the recording ended in {outcome:?}"
+            ),
+            None => info!("replay finished with {enemy_count} enemies remaining"),
+        }
+        return;
+    };
+    tick.keys.apply(&mut keyboard);
+    tick.mouse_buttons.apply(&mut mouse_button);
+    motion_events.clear();
+    for &delta in &tick.mouse_motion {
+        motion_events.send(MouseMotion { delta });
+    }
+    wheel_events.clear();
+    for &delta in &tick.mouse_wheel {
+        wheel_events.send(MouseWheel {
+            unit: MouseScrollUnit::Line,
+            x: delta.x,
+            y: delta.y,
+            window: Entity::PLACEHOLDER,
+        });
+    }
+    replay.cursor += 1;
+}
+
+fn resume_replay(replay: Option<Res<Replay>>, mut next_state: ResMut<NextState<GameState>>) {
+    if replay.is_some_and(|replay| replay.started && !replay.finished) {
+        next_state.set(GameState::Play);
+    }
+}
+
+fn finish_replay(
+    replay: Option<ResMut<Replay>>,
+    state: Res<State<GameState>>,
+    enemy_count: Res<EnemyCount>,
+) {
+    let Some(mut replay) = replay else {
+        return;
+    };
+    if !replay.started || replay.finished {
+        return;
+    }
+    replay.finished = true;
+    let outcome = Outcome {
+        state: *state.get(),
+        enemy_count: enemy_count.0,
+    };
+    let remaining = replay.recording.ticks.len() - replay.cursor;
+    replay.reproduced = replay.recording.outcome.as_ref() == Some(&outcome) && remaining == 0;
+    if replay.reproduced {
+        info!("replay reproduced the recorded outcome {outcome:?}");
+    } else {
+        warn!(
+            "replay diverged: ended in {outcome:?} with {remaining} ticks left, the recording ended in {:?}",
+            replay.recording.outcome
+        );
+    }
+}
+
+fn exit_finished_replay(replay: Res<Replay>, mut exit_writer: EventWriter<AppExit>) {
+    if !replay.finished {
+        return;
+    }
+    exit_writer.send(if replay.reproduced {
+        AppExit::Success
+    } else {
+        AppExit::error()
+    });
+}
@@ -0,0 +1,36 @@
+use bevy::prelude::*;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::simple_gun_battle::AppState;
+
+use super::{GameState, SceneIndex};
+
+pub fn plugin(app: &mut App) {
+    app.init_resource::<RngSeed>()
+        .add_systems(OnEnter(GameState::Spawn), insert_game_rng)
+        .add_systems(OnExit(AppState::Game), remove_game_rng);
+}
+
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct RngSeed(pub u64);
+
+impl Default for RngSeed {
+    fn default() -> Self {
+        Self(rand::thread_rng().gen())
+    }
+}
+
+#[derive(Resource, Deref, DerefMut)]
+pub struct GameRng(ChaCha8Rng);
+
+fn insert_game_rng(mut commands: Commands, seed: Res<RngSeed>, scene_index: Res<SceneIndex>) {
+    info!("gameplay rng seed: {}, level {}", seed.0, scene_index.0);
+    let mut rng = ChaCha8Rng::seed_from_u64(seed.0);
+    rng.set_stream(scene_index.0 as u64);
+    commands.insert_resource(GameRng(rng));
+}
+
+fn remove_game_rng(mut commands: Commands) {
+    commands.remove_resource::<GameRng>();
+}
@@ -0,0 +1,194 @@
+use std::time::Duration;
+
+use avian3d::prelude::*;
+use bevy::{
+    core_pipeline::bloom::Bloom, prelude::*, scene::{SceneInstance, SceneInstanceReady}
+};
+
+use crate::simple_gun_battle::{
+    game::{
+        inventory::Inventory,
+        level::Levels,
+        play::{standing_collider, CharacterController, PlayerView},
+        weapon::{FireControl, WeaponDef},
+        GameState, HealthPoints, SceneIndex,
+    },
+    settings::Settings,
+    AppState,
+};
+
+const PLAYER_HEALTH: f32 = 100.;
+
+pub fn plugin(app: &mut App) {
+    app.add_observer(despawn_scene_player)
+        .add_observer(spawn_to_play)
+        .add_systems(OnEnter(GameState::Spawn), (spawn_scene, spawn_player))
+        .add_systems(
+            Update,
+            (
+                update_field_of_view.never_param_warn(),
+                update_max_view_pitch.never_param_warn(),
+            )
+                .run_if(resource_changed::<Settings>),
+        );
+}
+
+#[derive(Component)]
+pub struct GameScene;
+
+#[derive(Component)]
+pub struct Player;
+
+#[derive(Component)]
+#[require(FireControl)]
+pub struct Gun {
+    pub weapon: Handle<WeaponDef>,
+}
+
+#[derive(Component)]
+pub struct GunModel;
+
+#[derive(Component)]
+pub struct PlayerCamera;
+
+#[derive(Component)]
+pub struct GunFlame(pub Timer);
+
+#[derive(Event)]
+pub struct DespawnScenePlayer;
+
+fn spawn_scene(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    scene_index: Res<SceneIndex>,
+    levels: Levels,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    let Some(level) = levels.get(scene_index.0) else {
+        error!("level {} is missing from the level manifest", scene_index.0);
+        next_state.set(AppState::StartMenu);
+        return;
+    };
+    commands.spawn((
+        SceneRoot(asset_server.load(GltfAssetLabel::Scene(0).from_asset(level.scene.clone()))),
+        ColliderConstructorHierarchy::new(ColliderConstructor::ConvexHullFromMesh),
+        GameScene,
+    ));
+}
+
+#[allow(clippy::too_many_arguments)]
+fn spawn_player(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    asset_server: Res<AssetServer>,
+    scene_index: Res<SceneIndex>,
+    levels: Levels,
+    inventory: Res<Inventory>,
+    settings: Res<Settings>,
+) {
+    let spawn_point = levels
+        .get(scene_index.0)
+        .map_or(Vec3::new(0., 1.5, 0.), |level| level.player_spawn);
+    commands
+        .spawn((
+            Transform::from_translation(spawn_point),
+            Visibility::Visible,
+            RigidBody::Kinematic,
+            standing_collider(),
+            CharacterController::default(),
+            HealthPoints::new(PLAYER_HEALTH),
+            Player,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    Transform::default(),
+                    Visibility::Inherited,
+                    PlayerView::new(settings.max_view_pitch),
+                ))
+                .with_children(|parent| {
+                    let mut gun = parent.spawn((
+                        Transform::default(),
+                        Visibility::Inherited,
+                        Gun {
+                            weapon: inventory.active_weapon(),
+                        },
+                    ));
+                    if let Some(ammo) = inventory.active_ammo() {
+                        gun.insert(ammo);
+                    }
+                    gun.with_children(|parent| {
+                        parent.spawn((SceneRoot::default(), GunModel));
+                        parent.spawn((
+                            Mesh3d(meshes.add(Rectangle::new(1., 1.))),
+                            MeshMaterial3d(materials.add(StandardMaterial {
+                                base_color: Color::WHITE.with_alpha(0.).with_luminance(2.),
+                                base_color_texture: Some(asset_server.load("explosion.png")),
+                                unlit: true,
+                                alpha_mode: AlphaMode::AlphaToCoverage,
+                                ..default()
+                            })),
+                            Transform::from_xyz(0., 0., 1.4)
+                                .with_rotation(Quat::from_rotation_x(std::f32::consts::PI))
+                                .with_scale(Vec3::splat(0.7)),
+                            GunFlame({
+                                let mut timer = Timer::from_seconds(0.2, TimerMode::Once);
+                                timer.set_elapsed(Duration::from_secs_f32(0.2));
+                                timer
+                            }),
+                        ));
+                    });
+                    parent
+                        .spawn((
+                            Camera3d::default(),
+                            Camera {
+                                order: 1,
+                                hdr: true,
+                                ..default()
+                            },
+                            Bloom::NATURAL,
+                            Projection::from(PerspectiveProjection {
+                                fov: settings.field_of_view.to_radians(),
+                                ..default()
+                            }),
+                            Transform::from_xyz(0.2, 0.2, -0.3).looking_to(Vec3::Z, Vec3::Y),
+                            PlayerCamera,
+                        ))
+                        .with_child((Transform::default(), SpatialListener::new(0.15)));
+                });
+        });
+}
+
+fn update_field_of_view(
+    mut projection: Single<&mut Projection, With<PlayerCamera>>,
+    settings: Res<Settings>,
+) {
+    if let Projection::Perspective(perspective) = &mut **projection {
+        perspective.fov = settings.field_of_view.to_radians();
+    }
+}
+
+fn update_max_view_pitch(mut view: Single<&mut PlayerView>, settings: Res<Settings>) {
+    view.max_pitch = settings.max_view_pitch.to_radians();
+}
+
+fn spawn_to_play(
+    trigger: Trigger<SceneInstanceReady>,
+    game_scene: Single<&SceneInstance, With<GameScene>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if trigger.instance_id == ***game_scene {
+        next_state.set(GameState::Play);
+    }
+}
+
+fn despawn_scene_player(
+    _trigger: Trigger<DespawnScenePlayer>,
+    mut commands: Commands,
+    game_scene: Single<Entity, With<GameScene>>,
+    player: Single<Entity, With<Player>>,
+) {
+    commands.entity(*game_scene).despawn_recursive();
+    commands.entity(*player).despawn_recursive();
+}
@@ -0,0 +1,57 @@
+use bevy::prelude::*;
+
+use crate::simple_gun_battle::{ui_utils::Widgets, AppState};
+
+use super::{inventory::ResetInventory, spawn::DespawnScenePlayer, GameState, SceneIndex};
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(GameState::Victory), spawn_victory)
+        .add_systems(
+            Update,
+            pressed_victory_button.run_if(in_state(GameState::Victory)),
+        );
+}
+
+#[derive(Component)]
+enum VictoryButton {
+    Restart,
+    ReturnStartMenu,
+}
+
+fn spawn_victory(mut commands: Commands) {
+    commands
+        .column(
+            Val::Percent(10.),
+            Color::BLACK.with_alpha(0.5),
+            GameState::Victory,
+        )
+        .with_children(|parent| {
+            parent.title("victory.title");
+            parent.button("victory.restart", VictoryButton::Restart);
+            parent.button("common.start_menu", VictoryButton::ReturnStartMenu);
+        });
+}
+
+fn pressed_victory_button(
+    mut commands: Commands,
+    buttons: Query<(&Interaction, &VictoryButton), Changed<Interaction>>,
+    mut game_next_state: ResMut<NextState<GameState>>,
+    mut app_next_state: ResMut<NextState<AppState>>,
+    mut scene_index: ResMut<SceneIndex>,
+) {
+    for (interaction, victory_button) in &buttons {
+        if let Interaction::Pressed = interaction {
+            commands.trigger(DespawnScenePlayer);
+            match victory_button {
+                VictoryButton::Restart => {
+                    scene_index.0 = 0;
+                    commands.trigger(ResetInventory);
+                    game_next_state.set(GameState::Spawn);
+                }
+                VictoryButton::ReturnStartMenu => {
+                    app_next_state.set(AppState::StartMenu);
+                }
+            }
+        }
+    }
+}
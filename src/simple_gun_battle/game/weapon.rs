@@ -0,0 +1,171 @@
+use std::ops::Range;
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, LoadContext},
+    prelude::*,
+};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+pub fn plugin(app: &mut App) {
+    app.init_asset::<WeaponDef>()
+        .register_asset_loader(WeaponDefLoader);
+}
+
+#[derive(Asset, TypePath, Debug, Clone)]
+pub struct WeaponDef {
+    pub name: String,
+    pub model: Handle<Scene>,
+    pub damage: Range<f32>,
+    pub pellets: u32,
+    pub ballistics: Ballistics,
+    pub fire_rate: f32,
+    pub fire_mode: FireMode,
+    pub spread_degrees: f32,
+    pub magazine_size: u32,
+    pub reserve_ammo: u32,
+    pub reload_seconds: f32,
+    pub muzzle_flash: MuzzleFlash,
+    pub sound: Handle<AudioSource>,
+    pub empty_sound: Handle<AudioSource>,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FireMode {
+    SemiAuto,
+    Burst(u32),
+    FullAuto,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum Ballistics {
+    Projectile { muzzle_velocity: f32 },
+    Hitscan { range: f32 },
+}
+
+#[derive(Deserialize, Debug, Clone, Copy)]
+pub struct MuzzleFlash {
+    pub size: f32,
+    pub offset: Vec3,
+    pub seconds: f32,
+}
+
+#[derive(Component, Default)]
+pub struct FireControl {
+    pub cooldown: f32,
+    pub burst_remaining: u32,
+    pub trigger_buffer: f32,
+    pub trigger_held: bool,
+}
+
+#[derive(Component, Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct Ammo {
+    pub magazine: u32,
+    pub reserve: u32,
+}
+
+#[derive(Component)]
+pub struct Reloading(pub Timer);
+
+#[derive(Deserialize)]
+struct WeaponDefFile {
+    name: String,
+    model: String,
+    damage: Range<f32>,
+    #[serde(default = "default_pellets")]
+    pellets: u32,
+    ballistics: Ballistics,
+    fire_rate: f32,
+    fire_mode: FireMode,
+    spread_degrees: f32,
+    magazine_size: u32,
+    reserve_ammo: u32,
+    reload_seconds: f32,
+    muzzle_flash: MuzzleFlash,
+    sound: String,
+    empty_sound: String,
+}
+
+fn default_pellets() -> u32 {
+    1
+}
+
+#[derive(Default)]
+struct WeaponDefLoader;
+
+#[derive(Debug, Error)]
+enum WeaponDefLoaderError {
+    #[error("could not read weapon definition: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("could not parse weapon definition: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+    #[error("weapon {name:?} has an empty damage range {range:?}")]
+    InvalidDamage { name: String, range: Range<f32> },
+    #[error("weapon {name:?} must have a positive muzzle velocity or range and fire rate")]
+    InvalidRate { name: String },
+    #[error("weapon {name:?} fires zero pellets per round")]
+    NoPellets { name: String },
+    #[error("weapon {name:?} fires bursts of zero rounds")]
+    EmptyBurst { name: String },
+    #[error("weapon {name:?} must have a non-empty magazine and a non-negative reload time")]
+    InvalidMagazine { name: String },
+}
+
+impl AssetLoader for WeaponDefLoader {
+    type Asset = WeaponDef;
+    type Settings = ();
+    type Error = WeaponDefLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &Self::Settings,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let file = ron::de::from_bytes::<WeaponDefFile>(&bytes)?;
+        if file.damage.is_empty() || file.damage.start < 0. {
+            return Err(WeaponDefLoaderError::InvalidDamage {
+                name: file.name,
+                range: file.damage,
+            });
+        }
+        let reach = match file.ballistics {
+            Ballistics::Projectile { muzzle_velocity } => muzzle_velocity,
+            Ballistics::Hitscan { range } => range,
+        };
+        if reach <= 0. || file.fire_rate <= 0. {
+            return Err(WeaponDefLoaderError::InvalidRate { name: file.name });
+        }
+        if file.pellets == 0 {
+            return Err(WeaponDefLoaderError::NoPellets { name: file.name });
+        }
+        if file.fire_mode == FireMode::Burst(0) {
+            return Err(WeaponDefLoaderError::EmptyBurst { name: file.name });
+        }
+        if file.magazine_size == 0 || file.reload_seconds < 0. {
+            return Err(WeaponDefLoaderError::InvalidMagazine { name: file.name });
+        }
+        Ok(WeaponDef {
+            model: load_context.load(GltfAssetLabel::Scene(0).from_asset(file.model)),
+            sound: load_context.load(file.sound),
+            empty_sound: load_context.load(file.empty_sound),
+            name: file.name,
+            damage: file.damage,
+            pellets: file.pellets,
+            ballistics: file.ballistics,
+            fire_rate: file.fire_rate,
+            fire_mode: file.fire_mode,
+            spread_degrees: file.spread_degrees.max(0.),
+            magazine_size: file.magazine_size,
+            reserve_ammo: file.reserve_ammo,
+            reload_seconds: file.reload_seconds,
+            muzzle_flash: file.muzzle_flash,
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["weapon.ron"]
+    }
+}
@@ -0,0 +1,39 @@
+use std::time::Duration;
+
+use bevy::{
+    app::ScheduleRunnerPlugin, audio::AudioLoader, gltf::GltfPlugin, input::InputPlugin,
+    prelude::*, render::mesh::MeshPlugin, scene::ScenePlugin, state::app::StatesPlugin,
+};
+
+use crate::simple_gun_battle::GunBattleConfig;
+
+const TICK_SECONDS: f64 = 1. / 60.;
+
+pub fn plugin(app: &mut App) {
+    let asset_root = app.world().resource::<GunBattleConfig>().asset_root.clone();
+    app.add_plugins((
+        MinimalPlugins.set(ScheduleRunnerPlugin::run_loop(Duration::from_secs_f64(
+            TICK_SECONDS,
+        ))),
+        TransformPlugin,
+        HierarchyPlugin,
+        InputPlugin,
+        StatesPlugin,
+        AssetPlugin {
+            file_path: asset_root,
+            ..default()
+        },
+        ScenePlugin,
+        MeshPlugin,
+        ImagePlugin::default(),
+        GltfPlugin::default(),
+    ))
+    .init_asset::<StandardMaterial>()
+    .init_asset::<AudioSource>()
+    .init_asset_loader::<AudioLoader>()
+    .register_type::<MeshMaterial3d<StandardMaterial>>()
+    .insert_resource(Headless);
+}
+
+#[derive(Resource)]
+pub struct Headless;
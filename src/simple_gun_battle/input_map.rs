@@ -0,0 +1,322 @@
+use std::collections::BTreeMap;
+
+use bevy::{
+    ecs::system::SystemParam,
+    input::{keyboard::NativeKeyCode, mouse::MouseWheel, InputSystem},
+    prelude::*,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::simple_gun_battle::{locale::Localized, settings::Settings};
+
+const DIGIT_KEYS: [KeyCode; 10] = [
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+    KeyCode::Digit8,
+    KeyCode::Digit9,
+    KeyCode::Digit0,
+];
+
+pub fn plugin(app: &mut App) {
+    app.init_resource::<WheelInput>().add_systems(
+        PreUpdate,
+        read_mouse_wheel
+            .in_set(ActionInputSystem)
+            .after(InputSystem),
+    );
+}
+
+/// Turns raw input into the per-frame state [`ActionInput`] reads. Systems
+/// that inject input, like replays, run before it.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ActionInputSystem;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Action {
+    MoveForward,
+    MoveBackward,
+    MoveLeft,
+    MoveRight,
+    Jump,
+    Sprint,
+    Crouch,
+    Fire,
+    Reload,
+    SelectWeapon(u8),
+    NextWeapon,
+    PrevWeapon,
+    Pause,
+    Exit,
+}
+
+impl Action {
+    pub const ALL: [Action; 16] = [
+        Action::MoveForward,
+        Action::MoveBackward,
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::Jump,
+        Action::Sprint,
+        Action::Crouch,
+        Action::Fire,
+        Action::Reload,
+        Action::SelectWeapon(0),
+        Action::SelectWeapon(1),
+        Action::SelectWeapon(2),
+        Action::NextWeapon,
+        Action::PrevWeapon,
+        Action::Pause,
+        Action::Exit,
+    ];
+
+    pub fn label(self) -> Localized {
+        match self {
+            Action::SelectWeapon(slot) => Localized::new("action.select_weapon").with_arg(
+                "slot",
+                Localized::verbatim((u32::from(slot) + 1).to_string()),
+            ),
+            action => Localized::new(action.key()),
+        }
+    }
+
+    fn key(self) -> &'static str {
+        match self {
+            Action::MoveForward => "action.move_forward",
+            Action::MoveBackward => "action.move_backward",
+            Action::MoveLeft => "action.move_left",
+            Action::MoveRight => "action.move_right",
+            Action::Jump => "action.jump",
+            Action::Sprint => "action.sprint",
+            Action::Crouch => "action.crouch",
+            Action::Fire => "action.fire",
+            Action::Reload => "action.reload",
+            Action::SelectWeapon(_) => "action.select_weapon",
+            Action::NextWeapon => "action.next_weapon",
+            Action::PrevWeapon => "action.prev_weapon",
+            Action::Pause => "action.pause",
+            Action::Exit => "action.exit",
+        }
+    }
+
+    pub fn default_binding(self) -> Binding {
+        match self {
+            Action::MoveForward => Binding::Key(KeyCode::KeyW),
+            Action::MoveBackward => Binding::Key(KeyCode::KeyS),
+            Action::MoveLeft => Binding::Key(KeyCode::KeyA),
+            Action::MoveRight => Binding::Key(KeyCode::KeyD),
+            Action::Jump => Binding::Key(KeyCode::Space),
+            Action::Sprint => Binding::Key(KeyCode::ShiftLeft),
+            Action::Crouch => Binding::Key(KeyCode::ControlLeft),
+            Action::Fire => Binding::Mouse(MouseButton::Left),
+            Action::Reload => Binding::Key(KeyCode::KeyR),
+            Action::SelectWeapon(slot) => Binding::Key(
+                DIGIT_KEYS
+                    .get(usize::from(slot))
+                    .copied()
+                    .unwrap_or(KeyCode::Unidentified(NativeKeyCode::Unidentified)),
+            ),
+            Action::NextWeapon => Binding::Wheel(WheelDirection::Down),
+            Action::PrevWeapon => Binding::Wheel(WheelDirection::Up),
+            Action::Pause => Binding::Key(KeyCode::KeyM),
+            Action::Exit => Binding::Key(KeyCode::Escape),
+        }
+    }
+
+    pub fn gamepad_button(self) -> Option<GamepadButton> {
+        match self {
+            Action::Jump => Some(GamepadButton::South),
+            Action::Sprint => Some(GamepadButton::LeftThumb),
+            Action::Crouch => Some(GamepadButton::East),
+            Action::Fire => Some(GamepadButton::RightTrigger2),
+            Action::Reload => Some(GamepadButton::West),
+            Action::NextWeapon => Some(GamepadButton::North),
+            Action::Pause => Some(GamepadButton::Start),
+            Action::MoveForward
+            | Action::MoveBackward
+            | Action::MoveLeft
+            | Action::MoveRight
+            | Action::SelectWeapon(_)
+            | Action::PrevWeapon
+            | Action::Exit => None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Binding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    Wheel(WheelDirection),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WheelDirection {
+    Up,
+    Down,
+}
+
+impl Binding {
+    pub fn label(self) -> Localized {
+        match self {
+            Binding::Key(key_code) => {
+                let name = format!("{key_code:?}");
+                Localized::verbatim(
+                    name.strip_prefix("Key")
+                        .or_else(|| name.strip_prefix("Digit"))
+                        .unwrap_or(&name),
+                )
+            }
+            Binding::Mouse(MouseButton::Left) => Localized::new("binding.mouse_left"),
+            Binding::Mouse(MouseButton::Right) => Localized::new("binding.mouse_right"),
+            Binding::Mouse(MouseButton::Middle) => Localized::new("binding.mouse_middle"),
+            Binding::Mouse(mouse_button) => Localized::new("binding.mouse_other")
+                .with_arg("button", Localized::verbatim(format!("{mouse_button:?}"))),
+            Binding::Wheel(WheelDirection::Up) => Localized::new("binding.wheel_up"),
+            Binding::Wheel(WheelDirection::Down) => Localized::new("binding.wheel_down"),
+        }
+    }
+}
+
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct InputBindings(BTreeMap<Action, Binding>);
+
+impl InputBindings {
+    pub fn get(&self, action: Action) -> Binding {
+        self.0
+            .get(&action)
+            .copied()
+            .unwrap_or_else(|| action.default_binding())
+    }
+
+    pub fn conflict(&self, action: Action, binding: Binding) -> Option<Action> {
+        Action::ALL
+            .into_iter()
+            .find(|other| *other != action && self.get(*other) == binding)
+    }
+
+    pub fn rebind(&mut self, action: Action, binding: Binding) -> Option<Action> {
+        let conflict = self.conflict(action, binding);
+        if let Some(other) = conflict {
+            let previous = self.get(action);
+            self.0.insert(other, previous);
+        }
+        self.0.insert(action, binding);
+        conflict
+    }
+
+    pub fn reset(&mut self) {
+        self.0.clear();
+    }
+}
+
+#[derive(Resource)]
+pub struct AwaitingBinding(pub Action);
+
+/// The direction the mouse wheel scrolled this frame, if any.
+#[derive(Resource, Default, PartialEq)]
+pub struct WheelInput(pub Option<WheelDirection>);
+
+#[derive(SystemParam)]
+pub struct ActionInput<'w, 's> {
+    bindings: Res<'w, InputBindings>,
+    settings: Res<'w, Settings>,
+    keyboard: Res<'w, ButtonInput<KeyCode>>,
+    mouse_button: Res<'w, ButtonInput<MouseButton>>,
+    wheel: Res<'w, WheelInput>,
+    gamepads: Query<'w, 's, &'static Gamepad>,
+    awaiting_binding: Option<Res<'w, AwaitingBinding>>,
+}
+
+impl ActionInput<'_, '_> {
+    pub fn pressed(&self, action: Action) -> bool {
+        if self.awaiting_binding.is_some() {
+            return false;
+        }
+        let bound = match self.bindings.get(action) {
+            Binding::Key(key_code) => self.keyboard.pressed(key_code),
+            Binding::Mouse(mouse_button) => self.mouse_button.pressed(mouse_button),
+            Binding::Wheel(direction) => self.wheel.0 == Some(direction),
+        };
+        bound
+            || action
+                .gamepad_button()
+                .is_some_and(|button| self.gamepads.iter().any(|gamepad| gamepad.pressed(button)))
+    }
+
+    pub fn just_pressed(&self, action: Action) -> bool {
+        if self.awaiting_binding.is_some() {
+            return false;
+        }
+        let bound = match self.bindings.get(action) {
+            Binding::Key(key_code) => self.keyboard.just_pressed(key_code),
+            Binding::Mouse(mouse_button) => self.mouse_button.just_pressed(mouse_button),
+            Binding::Wheel(direction) => self.wheel.0 == Some(direction),
+        };
+        bound
+            || action.gamepad_button().is_some_and(|button| {
+                self.gamepads
+                    .iter()
+                    .any(|gamepad| gamepad.just_pressed(button))
+            })
+    }
+
+    pub fn movement(&self) -> Vec2 {
+        let mut movement = Vec2::ZERO;
+        for (action, direction) in [
+            (Action::MoveForward, Vec2::Y),
+            (Action::MoveBackward, Vec2::NEG_Y),
+            (Action::MoveLeft, Vec2::NEG_X),
+            (Action::MoveRight, Vec2::X),
+        ] {
+            if self.pressed(action) {
+                movement += direction;
+            }
+        }
+        movement + self.stick(Gamepad::left_stick, 1.)
+    }
+
+    pub fn aim(&self) -> Vec2 {
+        self.stick(Gamepad::right_stick, self.settings.aim_response_curve)
+    }
+
+    fn stick(&self, read: impl Fn(&Gamepad) -> Vec2, response_curve: f32) -> Vec2 {
+        if self.awaiting_binding.is_some() {
+            return Vec2::ZERO;
+        }
+        self.gamepads
+            .iter()
+            .map(|gamepad| {
+                shape_stick(read(gamepad), self.settings.stick_dead_zone, response_curve)
+            })
+            .sum::<Vec2>()
+            .clamp_length_max(1.)
+    }
+}
+
+fn shape_stick(stick: Vec2, dead_zone: f32, response_curve: f32) -> Vec2 {
+    let magnitude = stick.length();
+    if magnitude <= dead_zone {
+        return Vec2::ZERO;
+    }
+    let scaled = ((magnitude - dead_zone) / (1. - dead_zone)).min(1.);
+    stick / magnitude * scaled.powf(response_curve)
+}
+
+fn read_mouse_wheel(mut wheel: ResMut<WheelInput>, mut wheel_reader: EventReader<MouseWheel>) {
+    let scroll: f32 = wheel_reader.read().map(|wheel| wheel.y).sum();
+    let direction = match scroll {
+        scroll if scroll > 0. => Some(WheelDirection::Up),
+        scroll if scroll < 0. => Some(WheelDirection::Down),
+        _ => None,
+    };
+    wheel.set_if_neq(WheelInput(direction));
+}
+
+pub fn action_just_pressed(action: Action) -> impl FnMut(ActionInput) -> bool + Clone {
+    move |actions: ActionInput| actions.just_pressed(action)
+}
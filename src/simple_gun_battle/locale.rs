@@ -0,0 +1,224 @@
+use bevy::{
+    asset::{io::Reader, AssetLoader, LoadContext},
+    ecs::system::SystemParam,
+    prelude::*,
+    ui::UiSystem,
+    utils::{HashMap, HashSet},
+    window::PrimaryWindow,
+};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::simple_gun_battle::settings::Settings;
+
+const FALLBACK_LANGUAGE: Language = Language::Chinese;
+
+pub fn plugin(app: &mut App) {
+    app.init_asset::<StringTable>()
+        .register_asset_loader(StringTableLoader)
+        .add_systems(Startup, load_string_tables)
+        .add_systems(PostUpdate, localize_texts.before(UiSystem::Prepare));
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Language {
+    #[default]
+    Chinese,
+    English,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::Chinese, Language::English];
+
+    pub fn code(self) -> &'static str {
+        match self {
+            Language::Chinese => "zh-CN",
+            Language::English => "en",
+        }
+    }
+
+    pub fn key(self) -> &'static str {
+        match self {
+            Language::Chinese => "language.zh-CN",
+            Language::English => "language.en",
+        }
+    }
+
+    fn path(self) -> String {
+        format!("locales/{}.strings.ron", self.code())
+    }
+}
+
+#[derive(Asset, TypePath, Deserialize, Debug)]
+#[serde(transparent)]
+pub struct StringTable {
+    strings: HashMap<String, String>,
+}
+
+#[derive(Component, Debug, Clone, PartialEq)]
+#[require(Text)]
+pub enum Localized {
+    Key {
+        key: String,
+        args: Vec<(&'static str, Localized)>,
+    },
+    Verbatim(String),
+}
+
+impl Default for Localized {
+    fn default() -> Self {
+        Localized::Verbatim(String::new())
+    }
+}
+
+impl Localized {
+    pub fn new(key: impl Into<String>) -> Self {
+        Localized::Key {
+            key: key.into(),
+            args: Vec::new(),
+        }
+    }
+
+    pub fn verbatim(text: impl Into<String>) -> Self {
+        Localized::Verbatim(text.into())
+    }
+
+    pub fn with_arg(mut self, name: &'static str, value: Localized) -> Self {
+        if let Localized::Key { args, .. } = &mut self {
+            args.push((name, value));
+        }
+        self
+    }
+}
+
+#[derive(Resource)]
+struct StringTables(HashMap<Language, Handle<StringTable>>);
+
+/// Resolves [`Localized`] text in the current language for text that is not
+/// a [`Text`] root, such as spans that are rebuilt every frame.
+#[derive(SystemParam)]
+pub struct Localizer<'w, 's> {
+    settings: Res<'w, Settings>,
+    string_tables: Res<'w, StringTables>,
+    tables: Res<'w, Assets<StringTable>>,
+    missing: Local<'s, HashSet<String>>,
+}
+
+impl Localizer<'_, '_> {
+    pub fn language(&self) -> Language {
+        self.settings.language
+    }
+
+    /// Returns `None` until the fallback string table has loaded.
+    pub fn text(&mut self, localized: &Localized) -> Option<String> {
+        let table = |language| {
+            self.string_tables
+                .0
+                .get(&language)
+                .and_then(|handle| self.tables.get(handle))
+        };
+        let translator = Translator {
+            current: table(self.settings.language),
+            fallback: table(FALLBACK_LANGUAGE)?,
+        };
+        Some(translator.text(localized, &mut self.missing))
+    }
+}
+
+struct Translator<'a> {
+    current: Option<&'a StringTable>,
+    fallback: &'a StringTable,
+}
+
+impl Translator<'_> {
+    fn text(&self, localized: &Localized, missing: &mut HashSet<String>) -> String {
+        let (key, args) = match localized {
+            Localized::Key { key, args } => (key, args),
+            Localized::Verbatim(text) => return text.clone(),
+        };
+        let template = self
+            .current
+            .and_then(|table| table.strings.get(key))
+            .or_else(|| self.fallback.strings.get(key));
+        let Some(template) = template else {
+            if missing.insert(key.clone()) {
+                warn!("missing translation for {key:?}");
+            }
+            return key.clone();
+        };
+        args.iter().fold(template.clone(), |text, (name, value)| {
+            text.replace(&format!("{{{name}}}"), &self.text(value, missing))
+        })
+    }
+}
+
+fn load_string_tables(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(StringTables(
+        Language::ALL
+            .into_iter()
+            .map(|language| (language, asset_server.load(language.path())))
+            .collect(),
+    ));
+}
+
+fn localize_texts(
+    mut localizer: Localizer,
+    mut table_events: EventReader<AssetEvent<StringTable>>,
+    mut language: Local<Option<Language>>,
+    mut texts: Query<(Ref<Localized>, &mut Text)>,
+    window: Option<Single<&mut Window, With<PrimaryWindow>>>,
+) {
+    let tables_changed = table_events.read().count() > 0;
+    let Some(title) = localizer.text(&Localized::new("window.title")) else {
+        return;
+    };
+    let refresh_all = tables_changed || *language != Some(localizer.language());
+    *language = Some(localizer.language());
+
+    for (localized, mut text) in &mut texts {
+        if !refresh_all && !localized.is_changed() {
+            continue;
+        }
+        let Some(localized) = localizer.text(&localized) else {
+            continue;
+        };
+        if text.0 != localized {
+            text.0 = localized;
+        }
+    }
+    if let Some(mut window) = window.filter(|_| refresh_all) {
+        window.title = title;
+    }
+}
+
+#[derive(Default)]
+struct StringTableLoader;
+
+#[derive(Debug, Error)]
+enum StringTableLoaderError {
+    #[error("could not read string table: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("could not parse string table: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+}
+
+impl AssetLoader for StringTableLoader {
+    type Asset = StringTable;
+    type Settings = ();
+    type Error = StringTableLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &Self::Settings,
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes::<StringTable>(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["strings.ron"]
+    }
+}
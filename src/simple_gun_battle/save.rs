@@ -0,0 +1,166 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::simple_gun_battle::{
+    game::{GameState, SceneIndex},
+    input_map::InputBindings,
+    settings::Settings,
+    GunBattleConfig,
+};
+
+pub const SAVE_FILE_VAR: &str = "SIMPLE_GUN_BATTLE_SAVE_FILE";
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(
+        Startup,
+        report_load_error.run_if(resource_exists::<SaveFileError>),
+    )
+    .add_systems(OnEnter(GameState::NextLevel), unlock_next_level)
+    .add_systems(
+        Last,
+        write_save_file.run_if(
+            resource_exists::<SaveFile>.and(
+                resource_changed::<Settings>
+                    .or(resource_changed::<InputBindings>)
+                    .or(resource_changed::<Progress>),
+            ),
+        ),
+    );
+}
+
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct Progress {
+    pub unlocked_level: usize,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+struct SaveData {
+    settings: Settings,
+    bindings: InputBindings,
+    progress: Progress,
+}
+
+#[derive(Resource)]
+struct SaveFile(PathBuf);
+
+#[derive(Resource, Debug, Error)]
+enum SaveFileError {
+    #[error("could not read save file {path}: {error}")]
+    Read { path: PathBuf, error: io::Error },
+    #[error("save file is corrupt, kept it as {backup}: {error}")]
+    Corrupt {
+        backup: PathBuf,
+        error: ron::error::SpannedError,
+    },
+    #[error("save file {path} is corrupt and could not be backed up: {error}")]
+    Backup { path: PathBuf, error: io::Error },
+}
+
+fn save_file_path(config: &GunBattleConfig) -> Option<PathBuf> {
+    config
+        .save_file
+        .clone()
+        .or_else(|| std::env::var_os(SAVE_FILE_VAR).map(PathBuf::from))
+        .or_else(|| dirs::config_dir().map(|dir| dir.join("simple_gun_battle").join("save.ron")))
+}
+
+fn backup_path(path: &Path) -> PathBuf {
+    let mut backup = path.as_os_str().to_owned();
+    backup.push(".bak");
+    backup.into()
+}
+
+pub fn load_save_file(app: &mut App) {
+    let path = save_file_path(app.world().resource::<GunBattleConfig>());
+    let mut save_data = SaveData::default();
+    if let Some(path) = path {
+        match read_save_file(&path) {
+            Ok(loaded) => {
+                if let Some(loaded) = loaded {
+                    save_data = loaded;
+                }
+                app.insert_resource(SaveFile(path));
+            }
+            Err(error @ SaveFileError::Corrupt { .. }) => {
+                app.insert_resource(error).insert_resource(SaveFile(path));
+            }
+            Err(error) => {
+                app.insert_resource(error);
+            }
+        }
+    }
+    app.insert_resource(save_data.settings.validated())
+        .insert_resource(save_data.bindings)
+        .insert_resource(save_data.progress);
+}
+
+fn read_save_file(path: &Path) -> Result<Option<SaveData>, SaveFileError> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(error) => {
+            return Err(SaveFileError::Read {
+                path: path.into(),
+                error,
+            })
+        }
+    };
+    match ron::from_str(&text) {
+        Ok(save_data) => Ok(Some(save_data)),
+        Err(error) => {
+            let backup = backup_path(path);
+            match fs::rename(path, &backup) {
+                Ok(()) => Err(SaveFileError::Corrupt { backup, error }),
+                Err(error) => Err(SaveFileError::Backup {
+                    path: path.into(),
+                    error,
+                }),
+            }
+        }
+    }
+}
+
+fn report_load_error(mut commands: Commands, error: Res<SaveFileError>) {
+    warn!("{}, using default settings", *error);
+    commands.remove_resource::<SaveFileError>();
+}
+
+fn unlock_next_level(mut progress: ResMut<Progress>, scene_index: Res<SceneIndex>) {
+    let next_level = scene_index.0 + 1;
+    if progress.unlocked_level < next_level {
+        progress.unlocked_level = next_level;
+    }
+}
+
+fn write_save_file(
+    save_file: Res<SaveFile>,
+    settings: Res<Settings>,
+    bindings: Res<InputBindings>,
+    progress: Res<Progress>,
+) {
+    let path = &save_file.0;
+    let save_data = SaveData {
+        settings: settings.clone(),
+        bindings: bindings.clone(),
+        progress: progress.clone(),
+    };
+    let result = ron::ser::to_string_pretty(&save_data, default())
+        .map_err(|error| error.to_string())
+        .and_then(|text| {
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir).map_err(|error| error.to_string())?;
+            }
+            fs::write(path, text).map_err(|error| error.to_string())
+        });
+    if let Err(error) = result {
+        error!("could not write save file {}: {error}", path.display());
+    }
+}
@@ -0,0 +1,545 @@
+use std::time::Duration;
+
+use bevy::{
+    audio::Volume,
+    prelude::*,
+    transform::TransformSystem,
+    window::{PrimaryWindow, WindowMode},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::simple_gun_battle::{
+    framepace::{FramepaceSettings, Limiter},
+    game::GameState,
+    locale::{Language, Localized},
+    ui_utils::{DropdownChanged, Slider, SliderChanged, ToggleChanged, Widgets, DEFAULT_FONT},
+    AppState,
+};
+
+const SENSITIVITY_STEP: f32 = 0.1;
+const SENSITIVITY_RANGE: (f32, f32) = (0.1, 3.);
+const DEAD_ZONE_STEP: f32 = 0.05;
+const DEAD_ZONE_RANGE: (f32, f32) = (0., 0.5);
+const RESPONSE_CURVE_STEP: f32 = 0.25;
+const RESPONSE_CURVE_RANGE: (f32, f32) = (1., 3.);
+const FIELD_OF_VIEW_STEP: f32 = 5.;
+const FIELD_OF_VIEW_RANGE: (f32, f32) = (30., 90.);
+const MAX_VIEW_PITCH_STEP: f32 = 1.;
+const MAX_VIEW_PITCH_RANGE: (f32, f32) = (45., 89.);
+const VOLUME_STEP: f32 = 0.1;
+const VOLUME_RANGE: (f32, f32) = (0., 1.);
+const FRAME_LIMITS: [FrameLimit; 7] = [
+    FrameLimit::Auto,
+    FrameLimit::Manual(30),
+    FrameLimit::Manual(60),
+    FrameLimit::Manual(120),
+    FrameLimit::Manual(144),
+    FrameLimit::Manual(240),
+    FrameLimit::Off,
+];
+const DISPLAY_MODES: [DisplayMode; 3] = [
+    DisplayMode::Windowed,
+    DisplayMode::BorderlessFullscreen,
+    DisplayMode::Fullscreen,
+];
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(AppState::Settings), spawn_start_settings)
+        .add_systems(OnEnter(GameState::Settings), spawn_game_settings)
+        .add_systems(
+            Update,
+            pressed_settings_button
+                .run_if(in_state(AppState::Settings).or(in_state(GameState::Settings))),
+        )
+        .add_systems(
+            Update,
+            (
+                apply_display_mode.never_param_warn(),
+                apply_frame_limit,
+                apply_master_volume,
+                apply_volume_to_playing_sounds,
+            )
+                .run_if(resource_changed::<Settings>),
+        )
+        .add_observer(change_slider_setting)
+        .add_observer(change_toggle_setting)
+        .add_observer(change_dropdown_setting)
+        .add_systems(
+            PostUpdate,
+            apply_effects_volume.before(TransformSystem::TransformPropagate),
+        );
+}
+
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct Settings {
+    pub mouse_sensitivity: f32,
+    pub invert_y: bool,
+    pub stick_dead_zone: f32,
+    pub aim_response_curve: f32,
+    pub field_of_view: f32,
+    pub max_view_pitch: f32,
+    pub master_volume: f32,
+    pub effects_volume: f32,
+    pub display_mode: DisplayMode,
+    pub frame_limit: FrameLimit,
+    pub language: Language,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            mouse_sensitivity: 1.,
+            invert_y: false,
+            stick_dead_zone: 0.15,
+            aim_response_curve: 1.5,
+            field_of_view: 45.,
+            max_view_pitch: 89.,
+            master_volume: 1.,
+            effects_volume: 1.,
+            display_mode: DisplayMode::BorderlessFullscreen,
+            frame_limit: FrameLimit::Auto,
+            language: Language::default(),
+        }
+    }
+}
+
+impl Settings {
+    /// Pulls values from a hand-edited or outdated save file back into the
+    /// ranges the settings screen offers.
+    pub fn validated(self) -> Self {
+        let default = Settings::default();
+        let clamp = |value: f32, (min, max): (f32, f32), default: f32| {
+            if value.is_finite() {
+                value.clamp(min, max)
+            } else {
+                default
+            }
+        };
+        Self {
+            mouse_sensitivity: clamp(
+                self.mouse_sensitivity,
+                SENSITIVITY_RANGE,
+                default.mouse_sensitivity,
+            ),
+            stick_dead_zone: clamp(
+                self.stick_dead_zone,
+                DEAD_ZONE_RANGE,
+                default.stick_dead_zone,
+            ),
+            aim_response_curve: clamp(
+                self.aim_response_curve,
+                RESPONSE_CURVE_RANGE,
+                default.aim_response_curve,
+            ),
+            field_of_view: clamp(
+                self.field_of_view,
+                FIELD_OF_VIEW_RANGE,
+                default.field_of_view,
+            ),
+            max_view_pitch: clamp(
+                self.max_view_pitch,
+                MAX_VIEW_PITCH_RANGE,
+                default.max_view_pitch,
+            ),
+            master_volume: clamp(self.master_volume, VOLUME_RANGE, default.master_volume),
+            effects_volume: clamp(self.effects_volume, VOLUME_RANGE, default.effects_volume),
+            frame_limit: self.frame_limit.nearest_offered(),
+            ..self
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DisplayMode {
+    Windowed,
+    BorderlessFullscreen,
+    Fullscreen,
+}
+
+impl DisplayMode {
+    pub fn window_mode(self) -> WindowMode {
+        match self {
+            DisplayMode::Windowed => WindowMode::Windowed,
+            DisplayMode::BorderlessFullscreen => {
+                WindowMode::BorderlessFullscreen(MonitorSelection::Primary)
+            }
+            DisplayMode::Fullscreen => WindowMode::Fullscreen(MonitorSelection::Primary),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameLimit {
+    Auto,
+    Manual(u32),
+    Off,
+}
+
+impl FrameLimit {
+    /// Snaps a manual limit the settings screen does not offer to the closest
+    /// one it does.
+    fn nearest_offered(self) -> FrameLimit {
+        let FrameLimit::Manual(fps) = self else {
+            return self;
+        };
+        if FRAME_LIMITS.contains(&self) {
+            return self;
+        }
+        let nearest = FRAME_LIMITS
+            .into_iter()
+            .filter_map(|option| match option {
+                FrameLimit::Manual(option_fps) => Some((option, option_fps.abs_diff(fps))),
+                _ => None,
+            })
+            .min_by_key(|(_, distance)| *distance)
+            .map_or(FrameLimit::Auto, |(option, _)| option);
+        warn!("frame limit of {fps} fps is not offered, using {nearest:?} instead");
+        nearest
+    }
+
+    fn limiter(self) -> Limiter {
+        match self {
+            FrameLimit::Auto => Limiter::Auto,
+            FrameLimit::Manual(fps) => {
+                Limiter::Manual(Duration::from_secs_f64(1. / f64::from(fps)))
+            }
+            FrameLimit::Off => Limiter::Off,
+        }
+    }
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+enum SettingField {
+    MouseSensitivity,
+    MaxViewPitch,
+    InvertY,
+    StickDeadZone,
+    AimResponseCurve,
+    FieldOfView,
+    MasterVolume,
+    EffectsVolume,
+    DisplayMode,
+    FrameLimit,
+    Language,
+}
+
+impl SettingField {
+    const ALL: [SettingField; 11] = [
+        SettingField::MouseSensitivity,
+        SettingField::MaxViewPitch,
+        SettingField::InvertY,
+        SettingField::StickDeadZone,
+        SettingField::AimResponseCurve,
+        SettingField::FieldOfView,
+        SettingField::MasterVolume,
+        SettingField::EffectsVolume,
+        SettingField::DisplayMode,
+        SettingField::FrameLimit,
+        SettingField::Language,
+    ];
+
+    fn key(self) -> &'static str {
+        match self {
+            SettingField::MouseSensitivity => "settings.mouse_sensitivity",
+            SettingField::MaxViewPitch => "settings.max_view_pitch",
+            SettingField::InvertY => "settings.invert_y",
+            SettingField::StickDeadZone => "settings.stick_dead_zone",
+            SettingField::AimResponseCurve => "settings.aim_response_curve",
+            SettingField::FieldOfView => "settings.field_of_view",
+            SettingField::MasterVolume => "settings.master_volume",
+            SettingField::EffectsVolume => "settings.effects_volume",
+            SettingField::DisplayMode => "settings.display_mode",
+            SettingField::FrameLimit => "settings.frame_limit",
+            SettingField::Language => "settings.language",
+        }
+    }
+
+    fn spawn_widget(self, parent: &mut ChildBuilder, settings: &Settings) {
+        match self {
+            SettingField::MouseSensitivity => parent.slider(
+                Slider::new(
+                    settings.mouse_sensitivity,
+                    SENSITIVITY_RANGE,
+                    SENSITIVITY_STEP,
+                )
+                .with_label(|value| format!("{value:.1}")),
+                self,
+            ),
+            SettingField::MaxViewPitch => parent.slider(
+                Slider::new(
+                    settings.max_view_pitch,
+                    MAX_VIEW_PITCH_RANGE,
+                    MAX_VIEW_PITCH_STEP,
+                )
+                .with_label(|value| format!("{value:.0}°")),
+                self,
+            ),
+            SettingField::InvertY => parent.toggle(settings.invert_y, self),
+            SettingField::StickDeadZone => parent.slider(
+                Slider::new(settings.stick_dead_zone, DEAD_ZONE_RANGE, DEAD_ZONE_STEP)
+                    .with_label(percent_label),
+                self,
+            ),
+            SettingField::AimResponseCurve => parent.slider(
+                Slider::new(
+                    settings.aim_response_curve,
+                    RESPONSE_CURVE_RANGE,
+                    RESPONSE_CURVE_STEP,
+                ),
+                self,
+            ),
+            SettingField::FieldOfView => parent.slider(
+                Slider::new(
+                    settings.field_of_view,
+                    FIELD_OF_VIEW_RANGE,
+                    FIELD_OF_VIEW_STEP,
+                )
+                .with_label(|value| format!("{value:.0}°")),
+                self,
+            ),
+            SettingField::MasterVolume => parent.slider(
+                Slider::new(settings.master_volume, VOLUME_RANGE, VOLUME_STEP)
+                    .with_label(percent_label),
+                self,
+            ),
+            SettingField::EffectsVolume => parent.slider(
+                Slider::new(settings.effects_volume, VOLUME_RANGE, VOLUME_STEP)
+                    .with_label(percent_label),
+                self,
+            ),
+            SettingField::DisplayMode => parent.dropdown(
+                DISPLAY_MODES.map(display_mode_key),
+                option_index(&DISPLAY_MODES, settings.display_mode),
+                self,
+            ),
+            SettingField::FrameLimit => parent.dropdown(
+                FRAME_LIMITS.map(frame_limit_key),
+                option_index(&FRAME_LIMITS, settings.frame_limit.nearest_offered()),
+                self,
+            ),
+            SettingField::Language => parent.dropdown(
+                Language::ALL.map(Language::key),
+                option_index(&Language::ALL, settings.language),
+                self,
+            ),
+        };
+    }
+}
+
+fn percent_label(value: f32) -> String {
+    format!("{:.0}%", value * 100.)
+}
+
+fn display_mode_key(display_mode: DisplayMode) -> String {
+    match display_mode {
+        DisplayMode::Windowed => "display_mode.windowed",
+        DisplayMode::BorderlessFullscreen => "display_mode.borderless_fullscreen",
+        DisplayMode::Fullscreen => "display_mode.fullscreen",
+    }
+    .into()
+}
+
+fn frame_limit_key(frame_limit: FrameLimit) -> String {
+    match frame_limit {
+        FrameLimit::Auto => "frame_limit.auto".into(),
+        FrameLimit::Manual(fps) => format!("frame_limit.{fps}"),
+        FrameLimit::Off => "frame_limit.off".into(),
+    }
+}
+
+fn option_index<T: PartialEq + std::fmt::Debug>(options: &[T], current: T) -> usize {
+    options
+        .iter()
+        .position(|option| *option == current)
+        .unwrap_or_else(|| panic!("{current:?} is not one of the options {options:?}"))
+}
+
+#[derive(Component)]
+enum SettingsButton {
+    Controls,
+    Back,
+}
+
+fn spawn_start_settings(commands: Commands, settings: Res<Settings>) {
+    spawn_settings(commands, &settings, AppState::Settings);
+}
+
+fn spawn_game_settings(commands: Commands, settings: Res<Settings>) {
+    spawn_settings(commands, &settings, GameState::Settings);
+}
+
+fn spawn_settings(mut commands: Commands, settings: &Settings, state_scoped: impl States) {
+    commands
+        .column(Val::Px(8.), Color::BLACK.with_alpha(0.5), state_scoped)
+        .with_children(|parent| {
+            parent.title("settings.title");
+            for field in SettingField::ALL {
+                parent
+                    .spawn(Node {
+                        align_items: AlignItems::Center,
+                        column_gap: Val::Px(24.),
+                        ..default()
+                    })
+                    .with_children(|parent| {
+                        parent.spawn((
+                            Localized::new(field.key()),
+                            setting_font(),
+                            Node {
+                                width: Val::Px(320.),
+                                ..default()
+                            },
+                        ));
+                        field.spawn_widget(parent, settings);
+                    });
+            }
+            parent
+                .spawn(Node {
+                    column_gap: Val::Px(24.),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.button("settings.controls", SettingsButton::Controls);
+                    parent.button("common.back", SettingsButton::Back);
+                });
+        });
+}
+
+fn setting_font() -> TextFont {
+    TextFont {
+        font: DEFAULT_FONT,
+        font_size: 45.,
+        ..default()
+    }
+}
+
+fn pressed_settings_button(
+    buttons: Query<(&Interaction, &SettingsButton), Changed<Interaction>>,
+    app_state: Res<State<AppState>>,
+    mut app_next_state: ResMut<NextState<AppState>>,
+    mut game_next_state: ResMut<NextState<GameState>>,
+) {
+    for (interaction, settings_button) in &buttons {
+        if let Interaction::Pressed = interaction {
+            match settings_button {
+                SettingsButton::Controls => {
+                    if *app_state.get() == AppState::Settings {
+                        app_next_state.set(AppState::Controls);
+                    } else {
+                        game_next_state.set(GameState::Controls);
+                    }
+                }
+                SettingsButton::Back => {
+                    if *app_state.get() == AppState::Settings {
+                        app_next_state.set(AppState::StartMenu);
+                    } else {
+                        game_next_state.set(GameState::Menu);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn change_slider_setting(
+    trigger: Trigger<SliderChanged>,
+    fields: Query<&SettingField>,
+    mut settings: ResMut<Settings>,
+) {
+    let Ok(field) = fields.get(trigger.entity()) else {
+        return;
+    };
+    let value = trigger.event().0;
+    match field {
+        SettingField::MouseSensitivity => settings.mouse_sensitivity = value,
+        SettingField::MaxViewPitch => settings.max_view_pitch = value,
+        SettingField::StickDeadZone => settings.stick_dead_zone = value,
+        SettingField::AimResponseCurve => settings.aim_response_curve = value,
+        SettingField::FieldOfView => settings.field_of_view = value,
+        SettingField::MasterVolume => settings.master_volume = value,
+        SettingField::EffectsVolume => settings.effects_volume = value,
+        _ => {}
+    }
+}
+
+fn change_toggle_setting(
+    trigger: Trigger<ToggleChanged>,
+    fields: Query<&SettingField>,
+    mut settings: ResMut<Settings>,
+) {
+    if let Ok(SettingField::InvertY) = fields.get(trigger.entity()) {
+        settings.invert_y = trigger.event().0;
+    }
+}
+
+fn change_dropdown_setting(
+    trigger: Trigger<DropdownChanged>,
+    fields: Query<&SettingField>,
+    mut settings: ResMut<Settings>,
+) {
+    let Ok(field) = fields.get(trigger.entity()) else {
+        return;
+    };
+    let selected = trigger.event().0;
+    match field {
+        SettingField::DisplayMode => settings.display_mode = DISPLAY_MODES[selected],
+        SettingField::FrameLimit => settings.frame_limit = FRAME_LIMITS[selected],
+        SettingField::Language => settings.language = Language::ALL[selected],
+        _ => {}
+    }
+}
+
+fn apply_display_mode(
+    mut window: Single<&mut Window, With<PrimaryWindow>>,
+    settings: Res<Settings>,
+) {
+    let mode = settings.display_mode.window_mode();
+    if window.mode != mode {
+        window.mode = mode;
+    }
+}
+
+fn apply_frame_limit(
+    framepace_settings: Option<ResMut<FramepaceSettings>>,
+    settings: Res<Settings>,
+) {
+    if let Some(mut framepace_settings) = framepace_settings {
+        framepace_settings.limiter = settings.frame_limit.limiter();
+    }
+}
+
+fn apply_master_volume(global_volume: Option<ResMut<GlobalVolume>>, settings: Res<Settings>) {
+    if let Some(mut global_volume) = global_volume {
+        global_volume.volume = Volume::new(settings.master_volume);
+    }
+}
+
+/// The volume a sound was spawned with, before any settings applied.
+#[derive(Component)]
+struct BaseVolume(f32);
+
+fn apply_effects_volume(
+    mut commands: Commands,
+    mut playback_settings: Query<(Entity, &mut PlaybackSettings), Added<PlaybackSettings>>,
+    settings: Res<Settings>,
+) {
+    for (entity, mut playback_settings) in &mut playback_settings {
+        let base_volume = playback_settings.volume.get();
+        commands.entity(entity).insert(BaseVolume(base_volume));
+        playback_settings.volume = Volume::new(base_volume * settings.effects_volume);
+    }
+}
+
+fn apply_volume_to_playing_sounds(
+    sinks: Query<(&AudioSink, &BaseVolume)>,
+    spatial_sinks: Query<(&SpatialAudioSink, &BaseVolume)>,
+    settings: Res<Settings>,
+) {
+    let scale = settings.master_volume * settings.effects_volume;
+    for (sink, base_volume) in &sinks {
+        sink.set_volume(base_volume.0 * scale);
+    }
+    for (sink, base_volume) in &spatial_sinks {
+        sink.set_volume(base_volume.0 * scale);
+    }
+}
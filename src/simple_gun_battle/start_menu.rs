@@ -1,6 +1,7 @@
 use bevy::prelude::*;
 
-use crate::{
+use crate::simple_gun_battle::{
+    game::Levels,
     ui_utils::Widgets,
     AppState,
 };
@@ -13,6 +14,7 @@ pub fn plugin(app: &mut App) {
 #[derive(Component)]
 enum StartMenuButton {
     StartGame,
+    Settings,
     ExitGame,
 }
 
@@ -20,9 +22,10 @@ fn spawn_start_menu(mut commands: Commands) {
     commands
         .column(Val::Percent(10.), Color::NONE, AppState::StartMenu)
         .with_children(|parent| {
-            parent.title("简单枪战！");
-            parent.button("开始游戏", StartMenuButton::StartGame);
-            parent.button("退出游戏", StartMenuButton::ExitGame);
+            parent.title("start_menu.title");
+            parent.button("start_menu.start_game", StartMenuButton::StartGame);
+            parent.button("start_menu.settings", StartMenuButton::Settings);
+            parent.button("start_menu.exit_game", StartMenuButton::ExitGame);
         });
 }
 
@@ -30,12 +33,20 @@ fn pressed_start_menu(
     buttons: Query<(&Interaction, &StartMenuButton), Changed<Interaction>>,
     mut next_state: ResMut<NextState<AppState>>,
     mut exit_writer: EventWriter<AppExit>,
+    levels: Levels,
 ) {
     for (interaction, start_menu_button) in &buttons {
         if let Interaction::Pressed = interaction {
             match start_menu_button {
                 StartMenuButton::StartGame => {
-                    next_state.set(AppState::Game);
+                    if levels.is_loaded() {
+                        next_state.set(AppState::Game);
+                    } else {
+                        warn!("level manifest is not loaded yet");
+                    }
+                }
+                StartMenuButton::Settings => {
+                    next_state.set(AppState::Settings);
                 }
                 StartMenuButton::ExitGame => {
                     exit_writer.send_default();
@@ -0,0 +1,554 @@
+use bevy::{
+    asset::load_internal_binary_asset,
+    color::palettes::tailwind,
+    input::keyboard::{Key, KeyboardInput},
+    prelude::*,
+    ui::{RelativeCursorPosition, UiPlugin},
+};
+use uuid::uuid;
+
+use crate::simple_gun_battle::{
+    focus::{Adjustable, Focused},
+    locale::Localized,
+};
+
+const PRESSED: Color = Color::Srgba(tailwind::RED_600);
+const HOVERED: Color = Color::Srgba(tailwind::PURPLE_300);
+const FOCUSED: Color = Color::Srgba(tailwind::AMBER_400);
+const NONE: Color = Color::Srgba(tailwind::ORANGE_500);
+const FOCUSED_BORDER: Color = Color::WHITE;
+const BORDER: Color = Color::BLACK;
+const SLIDER_FILL: Color = Color::Srgba(tailwind::RED_800);
+
+pub const DEFAULT_FONT: Handle<Font> = Handle::Weak(AssetId::Uuid {
+    uuid: uuid!("0efa080a-3128-4329-9cd1-76f1e116e824"),
+});
+
+pub fn plugin(app: &mut App) {
+    if app.is_plugin_added::<UiPlugin>() {
+        load_internal_binary_asset!(
+            app,
+            DEFAULT_FONT,
+            "NotoSansSC-Bold.ttf",
+            |bytes: &[u8], _path: String| Font::try_from_bytes(bytes.to_vec()).unwrap()
+        );
+        app.add_systems(Startup, spawn_ui_camera);
+    }
+
+    app.add_systems(
+        Update,
+        (
+            (
+                drag_slider,
+                adjust_focused_widget,
+                pressed_widget,
+                edit_text_input,
+            ),
+            (
+                change_button_background,
+                update_widget_labels,
+                update_slider_fill,
+            ),
+        )
+            .chain(),
+    );
+}
+
+#[derive(Event, Debug, Clone, Copy)]
+pub struct SliderChanged(pub f32);
+
+#[derive(Event, Debug, Clone, Copy)]
+pub struct ToggleChanged(pub bool);
+
+#[derive(Event, Debug, Clone, Copy)]
+pub struct DropdownChanged(pub usize);
+
+#[derive(Event, Debug, Clone)]
+pub struct TextInputChanged(pub String);
+
+#[derive(Event, Debug, Clone)]
+pub struct TextInputSubmitted(pub String);
+
+#[derive(Component, Debug, Clone)]
+#[require(Adjustable, RelativeCursorPosition)]
+pub struct Slider {
+    pub value: f32,
+    pub min: f32,
+    pub max: f32,
+    pub step: f32,
+    label: fn(f32) -> String,
+}
+
+impl Slider {
+    pub fn new(value: f32, (min, max): (f32, f32), step: f32) -> Self {
+        let mut slider = Self {
+            value,
+            min,
+            max,
+            step,
+            label: |value| format!("{value:.2}"),
+        };
+        slider.value = slider.snap(value);
+        slider
+    }
+
+    pub fn with_label(mut self, label: fn(f32) -> String) -> Self {
+        self.label = label;
+        self
+    }
+
+    fn snap(&self, value: f32) -> f32 {
+        let steps = ((value - self.min) / self.step).round();
+        (self.min + steps * self.step).clamp(self.min, self.max)
+    }
+
+    fn fraction(&self) -> f32 {
+        if self.max > self.min {
+            (self.value - self.min) / (self.max - self.min)
+        } else {
+            0.
+        }
+    }
+
+    fn set(&mut self, value: f32) -> bool {
+        let value = self.snap(value);
+        let changed = (value - self.value).abs() > f32::EPSILON;
+        self.value = value;
+        changed
+    }
+}
+
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Toggle(pub bool);
+
+#[derive(Component, Debug, Clone)]
+#[require(Adjustable)]
+pub struct Dropdown {
+    pub options: Vec<String>,
+    pub selected: usize,
+}
+
+impl Dropdown {
+    fn cycle(&mut self, direction: isize) {
+        let len = self.options.len() as isize;
+        if len > 0 {
+            self.selected = (self.selected as isize + direction).rem_euclid(len) as usize;
+        }
+    }
+}
+
+#[derive(Component, Debug, Clone)]
+#[require(Adjustable)]
+pub struct TextInput {
+    pub text: String,
+    pub max_length: usize,
+}
+
+#[derive(Component)]
+struct WidgetLabel;
+
+#[derive(Component)]
+struct SliderFill;
+
+pub trait SpawnUi: Send + Sync {
+    fn spawn_ui(&mut self, bundle: impl Bundle) -> EntityCommands;
+}
+
+pub trait Widgets: SpawnUi {
+    fn column(
+        &mut self,
+        row_gap: Val,
+        background: impl Into<Color>,
+        state_scoped: impl States,
+    ) -> EntityCommands;
+
+    fn title(&mut self, key: impl Into<String>) -> EntityCommands;
+
+    fn button(&mut self, key: impl Into<String>, marker: impl Component) -> EntityCommands;
+
+    fn slider(&mut self, slider: Slider, marker: impl Component) -> EntityCommands;
+
+    fn toggle(&mut self, on: bool, marker: impl Component) -> EntityCommands;
+
+    fn dropdown(
+        &mut self,
+        options: impl IntoIterator<Item = impl Into<String>>,
+        selected: usize,
+        marker: impl Component,
+    ) -> EntityCommands;
+
+    fn text_input(
+        &mut self,
+        text: impl Into<String>,
+        max_length: usize,
+        marker: impl Component,
+    ) -> EntityCommands;
+}
+
+impl SpawnUi for Commands<'_, '_> {
+    fn spawn_ui(&mut self, bundle: impl Bundle) -> EntityCommands {
+        self.spawn(bundle)
+    }
+}
+
+impl SpawnUi for ChildBuilder<'_> {
+    fn spawn_ui(&mut self, bundle: impl Bundle) -> EntityCommands {
+        self.spawn(bundle)
+    }
+}
+
+impl<T: SpawnUi> Widgets for T {
+    fn column(
+        &mut self,
+        row_gap: Val,
+        background: impl Into<Color>,
+        state_scoped: impl States,
+    ) -> EntityCommands {
+        self.spawn_ui((
+            Node {
+                flex_direction: FlexDirection::Column,
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                row_gap,
+                ..default()
+            },
+            BackgroundColor(background.into()),
+            StateScoped(state_scoped),
+        ))
+    }
+
+    fn title(&mut self, key: impl Into<String>) -> EntityCommands {
+        self.spawn_ui((
+            Localized::new(key),
+            TextFont {
+                font: DEFAULT_FONT,
+                font_size: 140.,
+                ..default()
+            },
+        ))
+    }
+
+    fn button(&mut self, key: impl Into<String>, marker: impl Component) -> EntityCommands {
+        let mut entity_commands = self.spawn_ui((
+            Button,
+            Node {
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                width: Val::Px(350.),
+                height: Val::Px(120.),
+                border: UiRect::all(Val::Px(8.)),
+                ..default()
+            },
+            BorderRadius::all(Val::Percent(50.)),
+            BorderColor(BORDER),
+            marker,
+        ));
+        entity_commands.with_child((
+            Localized::new(key),
+            TextFont {
+                font: DEFAULT_FONT,
+                font_size: 65.,
+                ..default()
+            },
+        ));
+        entity_commands
+    }
+
+    fn slider(&mut self, slider: Slider, marker: impl Component) -> EntityCommands {
+        let fraction = slider.fraction();
+        let label = slider_label(&slider);
+        let mut entity_commands = self.spawn_ui((widget_node(), slider, marker));
+        entity_commands.with_children(|parent| {
+            parent.spawn((
+                Node {
+                    position_type: PositionType::Absolute,
+                    left: Val::Px(0.),
+                    width: Val::Percent(fraction * 100.),
+                    height: Val::Percent(100.),
+                    ..default()
+                },
+                BackgroundColor(SLIDER_FILL),
+                SliderFill,
+            ));
+            parent.spawn(widget_label(label));
+        });
+        entity_commands
+    }
+
+    fn toggle(&mut self, on: bool, marker: impl Component) -> EntityCommands {
+        let mut entity_commands = self.spawn_ui((widget_node(), Toggle(on), marker));
+        entity_commands.with_child(widget_label(toggle_label(on)));
+        entity_commands
+    }
+
+    fn dropdown(
+        &mut self,
+        options: impl IntoIterator<Item = impl Into<String>>,
+        selected: usize,
+        marker: impl Component,
+    ) -> EntityCommands {
+        let dropdown = Dropdown {
+            options: options.into_iter().map(Into::into).collect(),
+            selected,
+        };
+        let label = dropdown_label(&dropdown);
+        let mut entity_commands = self.spawn_ui((widget_node(), dropdown, marker));
+        entity_commands.with_child(widget_label(label));
+        entity_commands
+    }
+
+    fn text_input(
+        &mut self,
+        text: impl Into<String>,
+        max_length: usize,
+        marker: impl Component,
+    ) -> EntityCommands {
+        let text_input = TextInput {
+            text: text.into().chars().take(max_length).collect(),
+            max_length,
+        };
+        let label = text_input_label(&text_input, false);
+        let mut entity_commands = self.spawn_ui((widget_node(), text_input, marker));
+        entity_commands.with_child(widget_label(label));
+        entity_commands
+    }
+}
+
+fn widget_node() -> impl Bundle {
+    (
+        Button,
+        Node {
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            width: Val::Px(350.),
+            height: Val::Px(76.),
+            border: UiRect::all(Val::Px(6.)),
+            overflow: Overflow::clip(),
+            ..default()
+        },
+        BorderRadius::all(Val::Px(38.)),
+        BorderColor(BORDER),
+    )
+}
+
+fn widget_label(label: Localized) -> impl Bundle {
+    (
+        label,
+        TextFont {
+            font: DEFAULT_FONT,
+            font_size: 45.,
+            ..default()
+        },
+        WidgetLabel,
+    )
+}
+
+fn slider_label(slider: &Slider) -> Localized {
+    Localized::verbatim((slider.label)(slider.value))
+}
+
+fn toggle_label(on: bool) -> Localized {
+    Localized::new(if on { "widget.on" } else { "widget.off" })
+}
+
+fn dropdown_label(dropdown: &Dropdown) -> Localized {
+    let option = dropdown
+        .options
+        .get(dropdown.selected)
+        .map_or_else(Localized::default, Localized::new);
+    Localized::new("widget.dropdown").with_arg("option", option)
+}
+
+fn text_input_label(text_input: &TextInput, focused: bool) -> Localized {
+    if focused {
+        Localized::verbatim(format!("{}|", text_input.text))
+    } else {
+        Localized::verbatim(text_input.text.clone())
+    }
+}
+
+#[derive(Component, Clone, Copy)]
+#[require(Camera2d)]
+pub struct UiCamera;
+
+fn spawn_ui_camera(mut commands: Commands) {
+    commands.spawn(UiCamera);
+}
+
+#[allow(clippy::type_complexity)]
+fn change_button_background(
+    mut buttons: Query<(
+        &Interaction,
+        Has<Focused>,
+        &mut BackgroundColor,
+        &mut BorderColor,
+    )>,
+    changed: Query<Entity, (With<Button>, Or<(Changed<Interaction>, Added<Focused>)>)>,
+    mut unfocused: RemovedComponents<Focused>,
+) {
+    let restyled: Vec<Entity> = changed.iter().chain(unfocused.read()).collect();
+    for entity in restyled {
+        let Ok((interaction, focused, mut background_color, mut border_color)) =
+            buttons.get_mut(entity)
+        else {
+            continue;
+        };
+        background_color.0 = match interaction {
+            Interaction::None if focused => FOCUSED,
+            Interaction::None => NONE,
+            Interaction::Hovered => HOVERED,
+            Interaction::Pressed => PRESSED,
+        };
+        border_color.0 = if focused { FOCUSED_BORDER } else { BORDER };
+    }
+}
+
+fn drag_slider(
+    mut commands: Commands,
+    mouse_button: Res<ButtonInput<MouseButton>>,
+    mut sliders: Query<(Entity, &Interaction, &RelativeCursorPosition, &mut Slider)>,
+) {
+    if !mouse_button.pressed(MouseButton::Left) {
+        return;
+    }
+    for (entity, interaction, cursor, mut slider) in &mut sliders {
+        let (Interaction::Pressed, Some(normalized)) = (interaction, cursor.normalized) else {
+            continue;
+        };
+        let value = slider.min + normalized.x.clamp(0., 1.) * (slider.max - slider.min);
+        if slider.set(value) {
+            commands.trigger_targets(SliderChanged(slider.value), entity);
+        }
+    }
+}
+
+fn adjust_focused_widget(
+    mut commands: Commands,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    mut sliders: Query<(Entity, &mut Slider), With<Focused>>,
+    mut dropdowns: Query<(Entity, &mut Dropdown), With<Focused>>,
+) {
+    let just_pressed = |key_code, button| {
+        keyboard.just_pressed(key_code)
+            || gamepads.iter().any(|gamepad| gamepad.just_pressed(button))
+    };
+    let direction = if just_pressed(KeyCode::ArrowLeft, GamepadButton::DPadLeft) {
+        -1
+    } else if just_pressed(KeyCode::ArrowRight, GamepadButton::DPadRight) {
+        1
+    } else {
+        return;
+    };
+    for (entity, mut slider) in &mut sliders {
+        let value = slider.value + direction as f32 * slider.step;
+        if slider.set(value) {
+            commands.trigger_targets(SliderChanged(slider.value), entity);
+        }
+    }
+    for (entity, mut dropdown) in &mut dropdowns {
+        dropdown.cycle(direction);
+        commands.trigger_targets(DropdownChanged(dropdown.selected), entity);
+    }
+}
+
+fn pressed_widget(
+    mut commands: Commands,
+    mut toggles: Query<(Entity, &Interaction, &mut Toggle), Changed<Interaction>>,
+    mut dropdowns: Query<(Entity, &Interaction, &mut Dropdown), Changed<Interaction>>,
+) {
+    for (entity, interaction, mut toggle) in &mut toggles {
+        if let Interaction::Pressed = interaction {
+            toggle.0 = !toggle.0;
+            commands.trigger_targets(ToggleChanged(toggle.0), entity);
+        }
+    }
+    for (entity, interaction, mut dropdown) in &mut dropdowns {
+        if let Interaction::Pressed = interaction {
+            dropdown.cycle(1);
+            commands.trigger_targets(DropdownChanged(dropdown.selected), entity);
+        }
+    }
+}
+
+fn edit_text_input(
+    mut commands: Commands,
+    mut keyboard_input: EventReader<KeyboardInput>,
+    mut text_inputs: Query<(Entity, &mut TextInput), With<Focused>>,
+) {
+    let Ok((entity, mut text_input)) = text_inputs.get_single_mut() else {
+        keyboard_input.clear();
+        return;
+    };
+    let mut changed = false;
+    for input in keyboard_input.read() {
+        if !input.state.is_pressed() {
+            continue;
+        }
+        let characters = match &input.logical_key {
+            Key::Character(characters) => characters.as_str(),
+            Key::Space => " ",
+            Key::Backspace => {
+                changed |= text_input.text.pop().is_some();
+                continue;
+            }
+            Key::Enter => {
+                commands.trigger_targets(TextInputSubmitted(text_input.text.clone()), entity);
+                continue;
+            }
+            _ => continue,
+        };
+        for character in characters.chars().filter(|c| !c.is_control()) {
+            if text_input.text.chars().count() < text_input.max_length {
+                text_input.text.push(character);
+                changed = true;
+            }
+        }
+    }
+    if changed {
+        commands.trigger_targets(TextInputChanged(text_input.text.clone()), entity);
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn update_widget_labels(
+    mut labels: Query<(&Parent, &mut Localized), With<WidgetLabel>>,
+    widgets: Query<(
+        Option<&Slider>,
+        Option<&Toggle>,
+        Option<&Dropdown>,
+        Option<&TextInput>,
+        Has<Focused>,
+    )>,
+) {
+    for (parent, mut localized) in &mut labels {
+        let Ok((slider, toggle, dropdown, text_input, focused)) = widgets.get(parent.get()) else {
+            continue;
+        };
+        let label = if let Some(slider) = slider {
+            slider_label(slider)
+        } else if let Some(toggle) = toggle {
+            toggle_label(toggle.0)
+        } else if let Some(dropdown) = dropdown {
+            dropdown_label(dropdown)
+        } else if let Some(text_input) = text_input {
+            text_input_label(text_input, focused)
+        } else {
+            continue;
+        };
+        localized.set_if_neq(label);
+    }
+}
+
+fn update_slider_fill(
+    mut fills: Query<(&Parent, &mut Node), With<SliderFill>>,
+    sliders: Query<&Slider, Changed<Slider>>,
+) {
+    for (parent, mut node) in &mut fills {
+        if let Ok(slider) = sliders.get(parent.get()) {
+            node.width = Val::Percent(slider.fraction() * 100.);
+        }
+    }
+}
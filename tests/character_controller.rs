@@ -0,0 +1,162 @@
+mod common;
+
+use std::time::Duration;
+
+use bevy::{
+    input::{keyboard::Key, ButtonState},
+    prelude::*,
+    time::TimeUpdateStrategy,
+};
+use common::{
+    headless_app, headless_app_at_level, player_controller, player_transform, send_key,
+    start_first_level, teleport_player, temp_save_file, update_until,
+};
+
+const COURSE_LEVEL: usize = 2;
+const TICKS_PER_SECOND: f32 = 64.;
+
+#[test]
+fn held_movement_keys_move_the_player_horizontally() {
+    temp_save_file("character_controller");
+    let mut app = headless_app();
+    start_first_level(&mut app);
+
+    let start = player_transform(app.world_mut()).translation;
+    send_key(
+        &mut app,
+        KeyCode::KeyW,
+        Key::Character("w".into()),
+        ButtonState::Pressed,
+    );
+    update_until(&mut app, "the player to walk", |world| {
+        player_transform(world)
+            .translation
+            .xz()
+            .distance(start.xz())
+            > 0.01
+    });
+    send_key(
+        &mut app,
+        KeyCode::KeyW,
+        Key::Character("w".into()),
+        ButtonState::Released,
+    );
+
+    let moved = player_transform(app.world_mut()).translation;
+    assert!((moved.y - start.y).abs() < 1e-4);
+}
+
+#[test]
+fn steep_ramps_block_the_player_and_walkable_ramps_are_climbed() {
+    let mut app = course_app("character_controller_ramps");
+
+    let (start, highest, furthest) = walk_forward_from(&mut app, Vec3::new(-15., 1.5, -4.), 40);
+    assert!(
+        highest.y - start.y < 0.05,
+        "climbed the steep ramp to {highest}"
+    );
+    assert!(furthest.z < 1., "walked into the steep ramp to {furthest}");
+
+    let (start, highest, _) = walk_forward_from(&mut app, Vec3::new(-5., 1.5, -4.), 40);
+    assert!(
+        highest.y - start.y > 1.5,
+        "only climbed the walkable ramp to {highest}"
+    );
+}
+
+#[test]
+fn steps_are_climbed_and_descended_without_leaving_the_ground() {
+    let mut app = course_app("character_controller_step");
+    teleport_player(app.world_mut(), Vec3::new(5., 1.5, -3.));
+    update_until(&mut app, "the player to land", |world| {
+        player_controller(world).grounded
+    });
+    let floor = player_transform(app.world_mut()).translation.y;
+
+    hold_key(&mut app, KeyCode::KeyW, "w", ButtonState::Pressed);
+    let mut on_step = false;
+    while player_transform(app.world_mut()).translation.z < 9. {
+        app.update();
+        let height = player_transform(app.world_mut()).translation.y - floor;
+        assert!(
+            player_controller(app.world_mut()).grounded,
+            "left the ground {height} above the floor"
+        );
+        on_step |= (height - 0.3).abs() < 0.01;
+    }
+    hold_key(&mut app, KeyCode::KeyW, "w", ButtonState::Released);
+
+    assert!(on_step, "never stood on the step");
+    assert!((player_transform(app.world_mut()).translation.y - floor).abs() < 0.01);
+}
+
+#[test]
+fn acceleration_is_reduced_in_the_air() {
+    let mut app = course_app("character_controller_air");
+    teleport_player(app.world_mut(), Vec3::new(15., 3.5, -12.));
+    update_until(&mut app, "the player to land on the ledge", |world| {
+        player_controller(world).grounded
+    });
+
+    hold_key(&mut app, KeyCode::KeyW, "w", ButtonState::Pressed);
+    let ground_change = velocity_change_per_tick(&mut app);
+    update_until(&mut app, "the player to walk off the ledge", |world| {
+        !player_controller(world).grounded
+    });
+    hold_key(&mut app, KeyCode::KeyW, "w", ButtonState::Released);
+    hold_key(&mut app, KeyCode::KeyS, "s", ButtonState::Pressed);
+    app.update();
+    let air_change = velocity_change_per_tick(&mut app);
+    hold_key(&mut app, KeyCode::KeyS, "s", ButtonState::Released);
+
+    let controller = player_controller(app.world_mut());
+    assert!(!controller.grounded);
+    assert!((ground_change - controller.ground_acceleration / TICKS_PER_SECOND).abs() < 1e-3);
+    assert!((air_change - controller.air_acceleration / TICKS_PER_SECOND).abs() < 1e-3);
+    assert!(air_change < ground_change);
+}
+
+fn course_app(name: &str) -> App {
+    temp_save_file(name);
+    let mut app = headless_app_at_level(COURSE_LEVEL);
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(
+        1. / TICKS_PER_SECOND,
+    )));
+    start_first_level(&mut app);
+    app
+}
+
+fn hold_key(app: &mut App, key_code: KeyCode, character: &str, state: ButtonState) {
+    send_key(app, key_code, Key::Character(character.into()), state);
+}
+
+fn walk_forward_from(app: &mut App, start: Vec3, ticks: usize) -> (Vec3, Vec3, Vec3) {
+    teleport_player(app.world_mut(), start);
+    update_until(app, "the player to land", |world| {
+        player_controller(world).grounded
+    });
+    let start = player_transform(app.world_mut()).translation;
+    let (mut highest, mut furthest) = (start, start);
+    hold_key(app, KeyCode::KeyW, "w", ButtonState::Pressed);
+    for _ in 0..ticks {
+        app.update();
+        let translation = player_transform(app.world_mut()).translation;
+        if translation.y > highest.y {
+            highest = translation;
+        }
+        if translation.z > furthest.z {
+            furthest = translation;
+        }
+    }
+    hold_key(app, KeyCode::KeyW, "w", ButtonState::Released);
+    app.update();
+    (start, highest, furthest)
+}
+
+fn velocity_change_per_tick(app: &mut App) -> f32 {
+    app.update();
+    let before = player_controller(app.world_mut()).velocity.xz();
+    app.update();
+    let after = player_controller(app.world_mut()).velocity.xz();
+    after.distance(before)
+}
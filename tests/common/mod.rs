@@ -0,0 +1,303 @@
+#![allow(dead_code)]
+
+use std::{cell::RefCell, error::Error, fs, path::PathBuf, time::Duration};
+
+use avian3d::prelude::*;
+use bevy::{
+    asset::{io::Reader, AssetLoader, LoadContext},
+    input::{
+        gamepad::{
+            GamepadConnection, GamepadConnectionEvent, RawGamepadButtonChangedEvent,
+            RawGamepadEvent,
+        },
+        keyboard::{Key, KeyboardInput},
+        mouse::MouseButtonInput,
+        ButtonState,
+    },
+    prelude::*,
+};
+use bevy_games_0_15::simple_gun_battle::{
+    AppState, CharacterController, GameState, GunBattleConfig, GunBattlePlugin, Player,
+};
+use serde::Deserialize;
+
+const MAX_FRAMES: usize = 1000;
+const FRAME_SLEEP: Duration = Duration::from_millis(2);
+
+thread_local! {
+    static SAVE_FILE: RefCell<Option<PathBuf>> = const { RefCell::new(None) };
+}
+
+/// Makes every app this test builds afterwards save to a fresh file of its
+/// own. Each test runs on its own thread, so parallel tests never share one.
+pub fn temp_save_file(name: &str) -> PathBuf {
+    let save_file = std::env::temp_dir().join(format!(
+        "simple_gun_battle_{name}_{}.ron",
+        std::process::id()
+    ));
+    let _ = fs::remove_file(&save_file);
+    SAVE_FILE.set(Some(save_file.clone()));
+    save_file
+}
+
+pub fn headless_app() -> App {
+    headless_app_at_level(0)
+}
+
+pub fn headless_app_at_level(starting_level: usize) -> App {
+    headless_app_with(GunBattleConfig {
+        starting_level,
+        seed: Some(0),
+        ..default()
+    })
+}
+
+pub fn headless_app_with(config: GunBattleConfig) -> App {
+    let mut app = App::new();
+    app.add_plugins(GunBattlePlugin {
+        config: GunBattleConfig {
+            asset_root: "tests/assets".into(),
+            headless: true,
+            save_file: config
+                .save_file
+                .or_else(|| SAVE_FILE.with_borrow(Clone::clone)),
+            ..config
+        },
+    })
+    .register_type::<ColliderConstructor>()
+    .register_asset_loader(StubSceneLoader);
+    app.finish();
+    app.cleanup();
+    app
+}
+
+pub fn update_until(app: &mut App, what: &str, mut done: impl FnMut(&mut World) -> bool) {
+    for _ in 0..MAX_FRAMES {
+        app.update();
+        if done(app.world_mut()) {
+            return;
+        }
+        std::thread::sleep(FRAME_SLEEP);
+    }
+    panic!(
+        "timed out waiting for {what} in {:?} / {:?}",
+        app_state(app),
+        game_state(app)
+    );
+}
+
+pub fn start_first_level(app: &mut App) {
+    update_until(app, "the level manifest to load", |world| {
+        let asset_server = world.resource::<AssetServer>();
+        asset_server
+            .get_path_id("campaign.levels.ron")
+            .is_some_and(|id| asset_server.is_loaded_with_dependencies(id))
+            && find_button(world, "开始游戏").is_some()
+    });
+    press_button(app, "开始游戏");
+    update_until(app, "the first level to play", |world| {
+        in_game_state(world, GameState::Play)
+    });
+    update_until(app, "the player to land", |world| {
+        player_controller(world).grounded
+    });
+}
+
+pub fn player_transform(world: &mut World) -> Transform {
+    *world
+        .query_filtered::<&Transform, With<Player>>()
+        .single(world)
+}
+
+pub fn teleport_player(world: &mut World, translation: Vec3) {
+    let (mut transform, mut controller) = world
+        .query_filtered::<(&mut Transform, &mut CharacterController), With<Player>>()
+        .single_mut(world);
+    transform.translation = translation;
+    controller.velocity = Vec3::ZERO;
+    controller.grounded = false;
+}
+
+pub fn player_controller(world: &mut World) -> CharacterController {
+    world
+        .query_filtered::<&CharacterController, With<Player>>()
+        .single(world)
+        .clone()
+}
+
+pub fn app_state(app: &App) -> Option<AppState> {
+    app.world()
+        .get_resource::<State<AppState>>()
+        .map(|state| *state.get())
+}
+
+pub fn game_state(app: &App) -> Option<GameState> {
+    app.world()
+        .get_resource::<State<GameState>>()
+        .map(|state| *state.get())
+}
+
+pub fn in_game_state(world: &World, game_state: GameState) -> bool {
+    world
+        .get_resource::<State<GameState>>()
+        .is_some_and(|state| *state.get() == game_state)
+}
+
+pub fn find_button(world: &mut World, label: &str) -> Option<Entity> {
+    let mut buttons = world.query_filtered::<(Entity, &Children), With<Button>>();
+    let mut texts = world.query::<&Text>();
+    buttons
+        .iter(world)
+        .find(|(_, children)| {
+            children
+                .iter()
+                .any(|child| texts.get(world, *child).is_ok_and(|text| text.0 == label))
+        })
+        .map(|(button, _)| button)
+}
+
+pub fn press_button(app: &mut App, label: &str) {
+    let button = find_button(app.world_mut(), label)
+        .unwrap_or_else(|| panic!("there is no {label:?} button"));
+    app.world_mut()
+        .entity_mut(button)
+        .insert(Interaction::Pressed);
+    app.update();
+}
+
+pub fn press_key(app: &mut App, key_code: KeyCode, logical_key: Key) {
+    for state in [ButtonState::Pressed, ButtonState::Released] {
+        send_key(app, key_code, logical_key.clone(), state);
+    }
+}
+
+pub fn send_key(app: &mut App, key_code: KeyCode, logical_key: Key, state: ButtonState) {
+    app.world_mut().send_event(KeyboardInput {
+        key_code,
+        logical_key,
+        state,
+        repeat: false,
+        window: Entity::PLACEHOLDER,
+    });
+    app.update();
+}
+
+pub fn send_mouse_button(app: &mut App, button: MouseButton, state: ButtonState) {
+    app.world_mut().send_event(MouseButtonInput {
+        button,
+        state,
+        window: Entity::PLACEHOLDER,
+    });
+    app.update();
+}
+
+pub fn connect_gamepad(app: &mut App) -> Entity {
+    let gamepad = app.world_mut().spawn_empty().id();
+    app.world_mut().send_event(GamepadConnectionEvent::new(
+        gamepad,
+        GamepadConnection::Connected {
+            name: "test gamepad".into(),
+            vendor_id: None,
+            product_id: None,
+        },
+    ));
+    app.update();
+    gamepad
+}
+
+pub fn press_gamepad_button(app: &mut App, gamepad: Entity, button: GamepadButton) {
+    for value in [1., 0.] {
+        app.world_mut()
+            .send_event(RawGamepadEvent::Button(RawGamepadButtonChangedEvent::new(
+                gamepad, button, value,
+            )));
+        app.update();
+    }
+}
+
+#[derive(Deserialize)]
+struct StubScene {
+    #[serde(default)]
+    enemies: Vec<Vec3>,
+    #[serde(default)]
+    boxes: Vec<StubBox>,
+}
+
+#[derive(Deserialize)]
+struct StubBox {
+    name: String,
+    translation: Vec3,
+    size: Vec3,
+    #[serde(default)]
+    rotation_degrees: Vec3,
+}
+
+impl StubScene {
+    fn build(&self) -> Scene {
+        let mut world = World::new();
+        world.spawn((
+            Name::new("floor"),
+            Transform::from_xyz(0., -0.5, 0.),
+            ColliderConstructor::Cuboid {
+                x_length: 60.,
+                y_length: 1.,
+                z_length: 60.,
+            },
+        ));
+        for &position in &self.enemies {
+            world.spawn((
+                Name::new("enemy_mesh"),
+                Transform::from_translation(position),
+                ColliderConstructor::Cuboid {
+                    x_length: 1.,
+                    y_length: 2.,
+                    z_length: 1.,
+                },
+            ));
+        }
+        for stub_box in &self.boxes {
+            let rotation = Quat::from_euler(
+                EulerRot::XYZ,
+                stub_box.rotation_degrees.x.to_radians(),
+                stub_box.rotation_degrees.y.to_radians(),
+                stub_box.rotation_degrees.z.to_radians(),
+            );
+            world.spawn((
+                Name::new(stub_box.name.clone()),
+                Transform::from_translation(stub_box.translation).with_rotation(rotation),
+                ColliderConstructor::Cuboid {
+                    x_length: stub_box.size.x,
+                    y_length: stub_box.size.y,
+                    z_length: stub_box.size.z,
+                },
+            ));
+        }
+        Scene::new(world)
+    }
+}
+
+#[derive(Default)]
+struct StubSceneLoader;
+
+impl AssetLoader for StubSceneLoader {
+    type Asset = Scene;
+    type Settings = ();
+    type Error = Box<dyn Error + Send + Sync>;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &Self::Settings,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let stub = ron::de::from_bytes::<StubScene>(&bytes)?;
+        load_context.add_labeled_asset(GltfAssetLabel::Scene(0).to_string(), stub.build());
+        Ok(stub.build())
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["stub"]
+    }
+}
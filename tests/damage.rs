@@ -0,0 +1,36 @@
+mod common;
+
+use bevy::prelude::*;
+use bevy_games_0_15::simple_gun_battle::{DamageEvent, DamageKind, HealthPoints, Player};
+use common::{headless_app, start_first_level, teleport_player, temp_save_file, update_until};
+
+const BESIDE_ENEMY: Vec3 = Vec3::new(1.98, 1.27, 15.);
+
+#[test]
+fn touching_an_enemy_hurts_the_player() {
+    temp_save_file("damage_contact");
+    let mut app = headless_app();
+    app.init_resource::<MeleeHits>().add_observer(record_melee);
+    start_first_level(&mut app);
+
+    teleport_player(app.world_mut(), BESIDE_ENEMY);
+    update_until(&mut app, "the enemy to hurt the player", |world| {
+        world.resource::<MeleeHits>().0 > 0 && player_health(world) < 100.
+    });
+}
+
+#[derive(Resource, Default)]
+struct MeleeHits(usize);
+
+fn record_melee(trigger: Trigger<DamageEvent>, mut hits: ResMut<MeleeHits>) {
+    if trigger.kind == DamageKind::Melee {
+        hits.0 += 1;
+    }
+}
+
+fn player_health(world: &mut World) -> f32 {
+    world
+        .query_filtered::<&HealthPoints, With<Player>>()
+        .single(world)
+        .current()
+}
@@ -0,0 +1,63 @@
+mod common;
+
+use bevy::{
+    input::{keyboard::Key, ButtonState},
+    prelude::*,
+};
+use bevy_games_0_15::simple_gun_battle::{AppState, Focused, Settings};
+use common::{headless_app, press_key, send_key, temp_save_file, update_until};
+
+#[test]
+fn keyboard_moves_focus_and_activates_buttons() {
+    temp_save_file("focus");
+    let mut app = headless_app();
+    update_until(&mut app, "the start menu to take focus", |world| {
+        is_focused(world, "开始游戏")
+    });
+
+    press_key(&mut app, KeyCode::Tab, Key::Tab);
+    assert!(is_focused(app.world_mut(), "设置"));
+    send_key(
+        &mut app,
+        KeyCode::ShiftLeft,
+        Key::Shift,
+        ButtonState::Pressed,
+    );
+    press_key(&mut app, KeyCode::Tab, Key::Tab);
+    send_key(
+        &mut app,
+        KeyCode::ShiftLeft,
+        Key::Shift,
+        ButtonState::Released,
+    );
+    assert!(is_focused(app.world_mut(), "开始游戏"));
+    press_key(&mut app, KeyCode::ArrowDown, Key::ArrowDown);
+    assert!(is_focused(app.world_mut(), "设置"));
+
+    press_key(&mut app, KeyCode::Enter, Key::Enter);
+    update_until(&mut app, "the settings screen to take focus", |world| {
+        *world.resource::<State<AppState>>().get() == AppState::Settings && is_focused(world, "1.0")
+    });
+    press_key(&mut app, KeyCode::ArrowRight, Key::ArrowRight);
+    let increased = app.world().resource::<Settings>().mouse_sensitivity;
+    assert!((increased - 1.1).abs() < 1e-4);
+    assert!(is_focused(app.world_mut(), "1.1"));
+
+    press_key(&mut app, KeyCode::ArrowDown, Key::ArrowDown);
+    assert!(is_focused(app.world_mut(), "89°"));
+    press_key(&mut app, KeyCode::ArrowDown, Key::ArrowDown);
+    assert!(is_focused(app.world_mut(), "关"));
+    press_key(&mut app, KeyCode::Enter, Key::Enter);
+    assert!(app.world().resource::<Settings>().invert_y);
+    assert!(is_focused(app.world_mut(), "开"));
+}
+
+fn is_focused(world: &mut World, label: &str) -> bool {
+    let mut focused = world.query_filtered::<&Children, (With<Button>, With<Focused>)>();
+    let mut texts = world.query::<&Text>();
+    focused.iter(world).any(|children| {
+        children
+            .iter()
+            .any(|child| texts.get(world, *child).is_ok_and(|text| text.0 == label))
+    })
+}
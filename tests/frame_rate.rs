@@ -0,0 +1,54 @@
+mod common;
+
+use std::time::Duration;
+
+use bevy::{
+    input::{keyboard::Key, ButtonState},
+    prelude::*,
+    time::TimeUpdateStrategy,
+};
+use common::{headless_app, player_transform, send_key, start_first_level, temp_save_file};
+
+const WALK_SECONDS: f64 = 1.;
+
+#[test]
+fn walking_distance_does_not_depend_on_frame_rate() {
+    let distances: Vec<f32> = [30., 60., 240.].into_iter().map(walking_distance).collect();
+    let tick_distance = 14. / 64.;
+    assert!(distances[0] > 5., "walked only {distances:?}");
+    for distance in &distances {
+        assert!(
+            (distance - distances[0]).abs() <= tick_distance,
+            "walked {distances:?} at 30, 60 and 240 fps"
+        );
+    }
+}
+
+fn walking_distance(fps: f64) -> f32 {
+    temp_save_file(&format!("frame_rate_{fps}"));
+    let mut app = headless_app();
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+        1. / fps,
+    )));
+    start_first_level(&mut app);
+
+    let start = player_transform(app.world_mut()).translation;
+    send_key(
+        &mut app,
+        KeyCode::KeyW,
+        Key::Character("w".into()),
+        ButtonState::Pressed,
+    );
+    for _ in 1..(WALK_SECONDS * fps).round() as usize {
+        app.update();
+    }
+    send_key(
+        &mut app,
+        KeyCode::KeyW,
+        Key::Character("w".into()),
+        ButtonState::Released,
+    );
+    player_transform(app.world_mut())
+        .translation
+        .distance(start)
+}
@@ -0,0 +1,53 @@
+mod common;
+
+use bevy::prelude::*;
+use bevy_games_0_15::simple_gun_battle::{AppState, Focused, GameState};
+use common::{
+    connect_gamepad, find_button, headless_app, in_game_state, press_gamepad_button,
+    temp_save_file, update_until,
+};
+
+const MAX_FOCUS_STEPS: usize = 10;
+
+#[test]
+fn gamepad_navigates_menus_and_pauses() {
+    temp_save_file("gamepad");
+    let mut app = headless_app();
+    update_until(&mut app, "the start menu", |world| {
+        find_button(world, "开始游戏").is_some()
+    });
+    let gamepad = connect_gamepad(&mut app);
+
+    focus_button(&mut app, gamepad, "开始游戏");
+    press_gamepad_button(&mut app, gamepad, GamepadButton::South);
+    update_until(&mut app, "the first level to play", |world| {
+        in_game_state(world, GameState::Play)
+    });
+
+    press_gamepad_button(&mut app, gamepad, GamepadButton::Start);
+    update_until(&mut app, "the pause menu", |world| {
+        in_game_state(world, GameState::Menu)
+    });
+
+    focus_button(&mut app, gamepad, "返回游戏");
+    press_gamepad_button(&mut app, gamepad, GamepadButton::South);
+    update_until(&mut app, "the level to resume", |world| {
+        in_game_state(world, GameState::Play)
+    });
+    assert_eq!(
+        *app.world().resource::<State<AppState>>().get(),
+        AppState::Game
+    );
+}
+
+fn focus_button(app: &mut App, gamepad: Entity, label: &str) {
+    for _ in 0..MAX_FOCUS_STEPS {
+        let world = app.world_mut();
+        let button = find_button(world, label);
+        if button.is_some_and(|button| world.entity(button).contains::<Focused>()) {
+            return;
+        }
+        press_gamepad_button(app, gamepad, GamepadButton::DPadDown);
+    }
+    panic!("could not focus the {label:?} button");
+}
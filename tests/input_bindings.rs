@@ -0,0 +1,132 @@
+mod common;
+
+use bevy::{
+    input::{
+        keyboard::Key,
+        mouse::{MouseScrollUnit, MouseWheel},
+    },
+    prelude::*,
+};
+use bevy_games_0_15::simple_gun_battle::{Action, AppState, Binding, GameState, InputBindings};
+use common::{
+    find_button, headless_app, in_game_state, press_button, press_key, start_first_level,
+    temp_save_file, update_until,
+};
+
+#[test]
+fn rebound_actions_drive_gameplay_and_survive_a_restart() {
+    temp_save_file("input_bindings");
+    let mut app = headless_app();
+
+    update_until(&mut app, "the start menu", |world| {
+        find_button(world, "设置").is_some()
+    });
+    press_button(&mut app, "设置");
+    update_until(&mut app, "the settings screen", |world| {
+        find_button(world, "按键设置").is_some()
+    });
+    press_button(&mut app, "按键设置");
+    update_until(&mut app, "the controls screen", |world| {
+        find_button(world, "M").is_some()
+    });
+
+    rebind(&mut app, "M", KeyCode::KeyP, "p");
+    assert_eq!(binding(&app, Action::Pause), Binding::Key(KeyCode::KeyP));
+
+    rebind(&mut app, "Space", KeyCode::KeyW, "w");
+    assert_eq!(binding(&app, Action::Jump), Binding::Key(KeyCode::KeyW));
+    assert_eq!(
+        binding(&app, Action::MoveForward),
+        Binding::Key(KeyCode::Space)
+    );
+    let bindings = app.world().resource::<InputBindings>().clone();
+
+    press_button(&mut app, "返回");
+    update_until(&mut app, "the settings screen", |world| {
+        *world.resource::<State<AppState>>().get() == AppState::Settings
+    });
+    press_button(&mut app, "返回");
+    update_until(&mut app, "the start menu", |world| {
+        find_button(world, "开始游戏").is_some()
+    });
+    press_button(&mut app, "开始游戏");
+    update_until(&mut app, "the first level to play", |world| {
+        in_game_state(world, GameState::Play)
+    });
+
+    press_key(&mut app, KeyCode::KeyM, Key::Character("m".into()));
+    assert!(in_game_state(app.world(), GameState::Play));
+    press_key(&mut app, KeyCode::KeyP, Key::Character("p".into()));
+    update_until(&mut app, "the pause menu", |world| {
+        in_game_state(world, GameState::Menu)
+    });
+    drop(app);
+
+    let app = headless_app();
+    assert_eq!(*app.world().resource::<InputBindings>(), bindings);
+}
+
+#[test]
+fn weapon_selection_goes_through_rebindable_actions() {
+    temp_save_file("input_bindings_weapons");
+    let mut app = headless_app();
+    let bindings = app.world().resource::<InputBindings>();
+    assert_eq!(
+        bindings.conflict(Action::Jump, Binding::Key(KeyCode::Digit1)),
+        Some(Action::SelectWeapon(0))
+    );
+    start_first_level(&mut app);
+    update_until(&mut app, "the rifle to be drawn", |world| {
+        hud_shows(world, "步枪")
+    });
+
+    press_key(&mut app, KeyCode::Digit1, Key::Character("1".into()));
+    update_until(&mut app, "the pistol to be drawn", |world| {
+        hud_shows(world, "手枪")
+    });
+    scroll(&mut app, -1.);
+    update_until(&mut app, "the next weapon to be drawn", |world| {
+        hud_shows(world, "步枪")
+    });
+
+    app.world_mut()
+        .resource_mut::<InputBindings>()
+        .rebind(Action::SelectWeapon(2), Binding::Key(KeyCode::KeyQ));
+    press_key(&mut app, KeyCode::KeyQ, Key::Character("q".into()));
+    update_until(&mut app, "the shotgun to be drawn", |world| {
+        hud_shows(world, "霰弹枪")
+    });
+    scroll(&mut app, 1.);
+    update_until(&mut app, "the previous weapon to be drawn", |world| {
+        hud_shows(world, "步枪")
+    });
+}
+
+fn scroll(app: &mut App, y: f32) {
+    app.world_mut().send_event(MouseWheel {
+        unit: MouseScrollUnit::Line,
+        x: 0.,
+        y,
+        window: Entity::PLACEHOLDER,
+    });
+    app.update();
+}
+
+fn hud_shows(world: &mut World, text: &str) -> bool {
+    world
+        .query::<&TextSpan>()
+        .iter(world)
+        .any(|span| span.0.contains(text))
+}
+
+fn rebind(app: &mut App, current: &str, key_code: KeyCode, character: &str) {
+    press_button(app, current);
+    update_until(app, "the binding prompt", |world| {
+        find_button(world, "按下新按键…").is_some()
+    });
+    press_key(app, key_code, Key::Character(character.into()));
+}
+
+fn binding(app: &App, action: Action) -> Binding {
+    app.world().resource::<InputBindings>().get(action)
+}
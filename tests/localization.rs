@@ -0,0 +1,80 @@
+mod common;
+
+use bevy::{
+    input::{keyboard::Key, ButtonState},
+    prelude::*,
+};
+use bevy_games_0_15::simple_gun_battle::{Ammo, Language, Reloading, Settings};
+use common::{
+    find_button, headless_app, send_key, start_first_level, temp_save_file, update_until,
+};
+
+#[test]
+fn switching_language_re_renders_text_and_falls_back_for_missing_keys() {
+    temp_save_file("localization");
+    let mut app = headless_app();
+    update_until(&mut app, "the start menu to be translated", |world| {
+        find_button(world, "开始游戏").is_some()
+    });
+
+    app.world_mut().resource_mut::<Settings>().language = Language::English;
+    app.update();
+    let world = app.world_mut();
+    assert!(find_button(world, "Start Game").is_some());
+    assert!(find_button(world, "Settings").is_some());
+    assert!(find_button(world, "开始游戏").is_none());
+    assert!(
+        find_button(world, "退出游戏").is_some(),
+        "keys missing from the English table fall back to Chinese"
+    );
+
+    app.world_mut().resource_mut::<Settings>().language = Language::Chinese;
+    app.update();
+    assert!(find_button(app.world_mut(), "开始游戏").is_some());
+}
+
+#[test]
+fn hud_shows_level_names_and_reloads_in_the_current_language() {
+    temp_save_file("localization_hud");
+    let mut app = headless_app();
+    start_first_level(&mut app);
+    app.world_mut().resource_mut::<Settings>().language = Language::English;
+    update_until(&mut app, "the level name to be translated", |world| {
+        hud_contains(world, "Arena 0  Enemies: ")
+    });
+
+    update_until(&mut app, "the gun to be loaded", |world| {
+        world.query::<&Ammo>().iter(world).next().is_some()
+    });
+    app.world_mut()
+        .query::<&mut Ammo>()
+        .single_mut(app.world_mut())
+        .magazine = 0;
+    send_key(
+        &mut app,
+        KeyCode::KeyR,
+        Key::Character("r".into()),
+        ButtonState::Pressed,
+    );
+    update_until(&mut app, "the reload to show on the HUD", |world| {
+        hud_contains(world, "Reloading")
+    });
+
+    app.world_mut().resource_mut::<Settings>().language = Language::Chinese;
+    app.update();
+    let world = app.world_mut();
+    assert!(world.query::<&Reloading>().iter(world).next().is_some());
+    assert!(hud_contains(world, "装填中"));
+    assert!(hud_contains(world, "竞技场 0  敌人总数："));
+}
+
+fn hud_contains(world: &mut World, text: &str) -> bool {
+    world
+        .query::<&Text>()
+        .iter(world)
+        .any(|hud| hud.0.contains(text))
+        || world
+            .query::<&TextSpan>()
+            .iter(world)
+            .any(|hud| hud.0.contains(text))
+}
@@ -0,0 +1,321 @@
+mod common;
+
+use std::{f32::consts::SQRT_2, time::Duration};
+
+use bevy::{
+    input::{keyboard::Key, ButtonState},
+    prelude::*,
+    time::TimeUpdateStrategy,
+};
+use bevy_games_0_15::simple_gun_battle::{
+    Ammo, DamageEvent, EnemyAi, MovementState, Player, Reloading, Stamina, STAMINA_RECOVERED,
+};
+use common::{
+    headless_app, headless_app_at_level, player_controller, player_transform, send_key,
+    send_mouse_button, start_first_level, teleport_player, temp_save_file, update_until,
+};
+
+const TICKS_PER_SECOND: f32 = 64.;
+const COURSE_LEVEL: usize = 2;
+const WALL_LEVEL: usize = 3;
+const RIFLE_SPREAD_DEGREES: f32 = 1.;
+
+#[test]
+fn holding_crouch_lowers_the_player_until_released() {
+    temp_save_file("movement_states");
+    let mut app = headless_app();
+    start_first_level(&mut app);
+
+    let standing = player_transform(app.world_mut()).translation.y;
+    key(&mut app, Control::Crouch, ButtonState::Pressed);
+    update_until(&mut app, "the player to crouch", |world| {
+        player_transform(world).translation.y < standing - 0.25
+    });
+    assert!((player_transform(app.world_mut()).translation.y - (standing - 0.5)).abs() < 1e-4);
+
+    key(&mut app, Control::Crouch, ButtonState::Released);
+    update_until(&mut app, "the player to stand up", |world| {
+        (player_transform(world).translation.y - standing).abs() < 1e-4
+    });
+}
+
+#[test]
+fn crouching_slides_only_out_of_a_sprint() {
+    temp_save_file("movement_states_slide");
+    let mut app = fixed_tick_app(0);
+
+    run_up(&mut app, false);
+    assert_eq!(movement_state(app.world_mut()), MovementState::Walking);
+    key(&mut app, Control::Crouch, ButtonState::Pressed);
+    ticks(&mut app, 3);
+    assert_eq!(movement_state(app.world_mut()), MovementState::Crouching);
+    release_all(&mut app);
+
+    run_up(&mut app, true);
+    assert_eq!(movement_state(app.world_mut()), MovementState::Sprinting);
+    key(&mut app, Control::Crouch, ButtonState::Pressed);
+    ticks(&mut app, 3);
+    assert_eq!(movement_state(app.world_mut()), MovementState::Sliding);
+    update_until(&mut app, "the slide to wear off", |world| {
+        movement_state(world) == MovementState::Crouching
+    });
+    release_all(&mut app);
+}
+
+#[test]
+fn sprinting_drains_stamina_until_exhausted_and_it_recovers() {
+    temp_save_file("movement_states_stamina");
+    let mut app = fixed_tick_app(0);
+
+    run_up(&mut app, true);
+    let controller = player_controller(app.world_mut());
+    assert!(controller.velocity.length() > controller.max_speed * 1.5);
+    let stamina = player_stamina(app.world_mut());
+    assert!(stamina.current < stamina.max);
+
+    player_stamina_mut(app.world_mut()).current = 5.;
+    update_until(&mut app, "the player to run out of stamina", |world| {
+        player_stamina(world).is_exhausted()
+    });
+    ticks(&mut app, 2);
+    assert_eq!(movement_state(app.world_mut()), MovementState::Walking);
+
+    update_until(&mut app, "the player to sprint again", |world| {
+        movement_state(world) == MovementState::Sprinting
+    });
+    let stamina = player_stamina(app.world_mut());
+    assert!((stamina.current - STAMINA_RECOVERED).abs() < 1.);
+    release_all(&mut app);
+}
+
+#[test]
+fn standing_up_is_blocked_under_a_low_ceiling() {
+    temp_save_file("movement_states_ceiling");
+    let mut app = fixed_tick_app(COURSE_LEVEL);
+    teleport_player(app.world_mut(), Vec3::new(25., 1.5, -6.));
+    update_until(&mut app, "the player to land", |world| {
+        player_controller(world).grounded
+    });
+    let standing = player_transform(app.world_mut()).translation.y;
+
+    key(&mut app, Control::Crouch, ButtonState::Pressed);
+    key(&mut app, Control::Forward, ButtonState::Pressed);
+    update_until(&mut app, "the player to crawl under the ceiling", |world| {
+        player_transform(world).translation.z > -0.5
+    });
+    key(&mut app, Control::Forward, ButtonState::Released);
+    key(&mut app, Control::Crouch, ButtonState::Released);
+    ticks(&mut app, 30);
+    assert_eq!(movement_state(app.world_mut()), MovementState::Crouching);
+    assert!((player_transform(app.world_mut()).translation.y - (standing - 0.5)).abs() < 1e-4);
+
+    key(&mut app, Control::Backward, ButtonState::Pressed);
+    update_until(&mut app, "the player to stand up in the open", |world| {
+        (player_transform(world).translation.y - standing).abs() < 1e-4
+    });
+    assert!(player_transform(app.world_mut()).translation.z < -2.);
+    key(&mut app, Control::Backward, ButtonState::Released);
+}
+
+#[test]
+fn sprinting_widens_the_spread_of_fired_shots() {
+    temp_save_file("movement_states_spread");
+    let mut app = fixed_tick_app(WALL_LEVEL);
+    app.init_resource::<HitPoints>().add_observer(record_hit);
+    update_until(&mut app, "the gun to be loaded", |world| {
+        world.query::<&Ammo>().iter(world).next().is_some()
+    });
+
+    key(&mut app, Control::Forward, ButtonState::Pressed);
+    ticks(&mut app, 30);
+    key(&mut app, Control::Forward, ButtonState::Released);
+    update_until(&mut app, "the player to stop at the wall", |world| {
+        movement_state(world) == MovementState::Idle
+    });
+    let idle = shot_angles(&mut app);
+
+    key(&mut app, Control::Forward, ButtonState::Pressed);
+    key(&mut app, Control::Sprint, ButtonState::Pressed);
+    ticks(&mut app, 3);
+    assert_eq!(movement_state(app.world_mut()), MovementState::Sprinting);
+    let sprinting = shot_angles(&mut app);
+    release_all(&mut app);
+
+    let idle_limit =
+        (RIFLE_SPREAD_DEGREES * MovementState::Idle.spread_scale()).to_radians() * SQRT_2;
+    assert!(idle.len() >= 8 && sprinting.len() >= 8);
+    assert!(idle.iter().all(|angle| *angle <= idle_limit + 1e-4));
+    assert!(sprinting.iter().any(|angle| *angle > idle_limit));
+}
+
+#[test]
+fn sprinting_is_heard_through_a_wall() {
+    temp_save_file("movement_states_noise");
+    let mut app = fixed_tick_app(WALL_LEVEL);
+
+    key(&mut app, Control::Forward, ButtonState::Pressed);
+    ticks(&mut app, 60);
+    assert_eq!(movement_state(app.world_mut()), MovementState::Walking);
+    assert!(enemy_ai(app.world_mut()).is_idle());
+
+    key(&mut app, Control::Sprint, ButtonState::Pressed);
+    update_until(&mut app, "the enemy to hear the player", |world| {
+        !enemy_ai(world).is_idle()
+    });
+    assert_eq!(movement_state(app.world_mut()), MovementState::Sprinting);
+    release_all(&mut app);
+}
+
+#[test]
+fn sprinting_interrupts_a_reload() {
+    temp_save_file("movement_states_reload");
+    let mut app = fixed_tick_app(0);
+    update_until(&mut app, "the gun to be loaded", |world| {
+        world.query::<&Ammo>().iter(world).next().is_some()
+    });
+    let magazine = gun_ammo(app.world_mut()).magazine;
+    set_magazine(app.world_mut(), 0);
+
+    tap(&mut app, Control::Reload);
+    update_until(&mut app, "the reload to start", is_reloading);
+    run_up(&mut app, true);
+    assert!(!is_reloading(app.world_mut()));
+    tap(&mut app, Control::Reload);
+    assert!(!is_reloading(app.world_mut()));
+    assert_eq!(gun_ammo(app.world_mut()).magazine, 0);
+    release_all(&mut app);
+
+    update_until(&mut app, "the player to stop", |world| {
+        movement_state(world) == MovementState::Idle
+    });
+    tap(&mut app, Control::Reload);
+    update_until(&mut app, "the reload to finish", |world| {
+        gun_ammo(world).magazine == magazine
+    });
+}
+
+#[derive(Resource, Default)]
+struct HitPoints(Vec<Vec3>);
+
+fn record_hit(trigger: Trigger<DamageEvent>, mut hit_points: ResMut<HitPoints>) {
+    if let Some(hit_point) = trigger.hit_point {
+        hit_points.0.push(hit_point);
+    }
+}
+
+fn shot_angles(app: &mut App) -> Vec<f32> {
+    app.world_mut().resource_mut::<HitPoints>().0.clear();
+    send_mouse_button(app, MouseButton::Left, ButtonState::Pressed);
+    ticks(app, 96);
+    send_mouse_button(app, MouseButton::Left, ButtonState::Released);
+
+    let world = app.world_mut();
+    let camera = *world
+        .query_filtered::<&GlobalTransform, With<Camera3d>>()
+        .single(world);
+    world
+        .resource::<HitPoints>()
+        .0
+        .iter()
+        .map(|hit_point| (*hit_point - camera.translation()).angle_between(*camera.forward()))
+        .collect()
+}
+
+fn fixed_tick_app(starting_level: usize) -> App {
+    let mut app = headless_app_at_level(starting_level);
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(
+        1. / TICKS_PER_SECOND,
+    )));
+    start_first_level(&mut app);
+    app
+}
+
+fn ticks(app: &mut App, count: usize) {
+    for _ in 0..count {
+        app.update();
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Control {
+    Forward,
+    Backward,
+    Sprint,
+    Crouch,
+    Reload,
+}
+
+impl Control {
+    fn key(self) -> (KeyCode, Key) {
+        match self {
+            Control::Forward => (KeyCode::KeyW, Key::Character("w".into())),
+            Control::Backward => (KeyCode::KeyS, Key::Character("s".into())),
+            Control::Sprint => (KeyCode::ShiftLeft, Key::Shift),
+            Control::Crouch => (KeyCode::ControlLeft, Key::Control),
+            Control::Reload => (KeyCode::KeyR, Key::Character("r".into())),
+        }
+    }
+}
+
+fn key(app: &mut App, control: Control, state: ButtonState) {
+    let (key_code, logical_key) = control.key();
+    send_key(app, key_code, logical_key, state);
+}
+
+fn tap(app: &mut App, control: Control) {
+    key(app, control, ButtonState::Pressed);
+    key(app, control, ButtonState::Released);
+}
+
+fn run_up(app: &mut App, sprint: bool) {
+    teleport_player(app.world_mut(), Vec3::new(0., 1.5, -25.));
+    update_until(app, "the player to land", |world| {
+        movement_state(world) == MovementState::Idle
+    });
+    key(app, Control::Forward, ButtonState::Pressed);
+    if sprint {
+        key(app, Control::Sprint, ButtonState::Pressed);
+    }
+    ticks(app, 40);
+}
+
+fn release_all(app: &mut App) {
+    for control in [Control::Forward, Control::Sprint, Control::Crouch] {
+        key(app, control, ButtonState::Released);
+    }
+}
+
+fn movement_state(world: &mut World) -> MovementState {
+    *world
+        .query_filtered::<&MovementState, With<Player>>()
+        .single(world)
+}
+
+fn player_stamina(world: &mut World) -> Stamina {
+    world
+        .query_filtered::<&Stamina, With<Player>>()
+        .single(world)
+        .clone()
+}
+
+fn player_stamina_mut(world: &mut World) -> Mut<Stamina> {
+    world
+        .query_filtered::<&mut Stamina, With<Player>>()
+        .single_mut(world)
+}
+
+fn enemy_ai(world: &mut World) -> &EnemyAi {
+    world.query::<&EnemyAi>().single(world)
+}
+
+fn gun_ammo(world: &mut World) -> Ammo {
+    *world.query::<&Ammo>().single(world)
+}
+
+fn set_magazine(world: &mut World, magazine: u32) {
+    world.query::<&mut Ammo>().single_mut(world).magazine = magazine;
+}
+
+fn is_reloading(world: &mut World) -> bool {
+    world.query::<&Reloading>().iter(world).next().is_some()
+}
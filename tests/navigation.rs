@@ -0,0 +1,102 @@
+mod common;
+
+use std::time::Duration;
+
+use avian3d::prelude::*;
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+use bevy_games_0_15::simple_gun_battle::{NavAgent, NavMesh};
+use common::{headless_app_at_level, start_first_level, temp_save_file, update_until};
+
+const TICKS_PER_SECOND: f32 = 64.;
+const WALL_LEVEL: usize = 3;
+const WALL_HALF_WIDTH: f32 = 10.;
+const BEHIND_WALL: Vec3 = Vec3::new(0., 1., -8.);
+const BEYOND_WALL: Vec3 = Vec3::new(0., 1., 8.);
+
+#[test]
+fn paths_lead_around_walls_and_never_into_them() {
+    temp_save_file("navigation");
+    let app = wall_app();
+    let nav_mesh = app.world().resource::<NavMesh>();
+
+    let path = nav_mesh
+        .find_path(BEHIND_WALL, BEYOND_WALL)
+        .expect("a path around the wall");
+    assert!(path
+        .iter()
+        .any(|waypoint| waypoint.x.abs() > WALL_HALF_WIDTH));
+    let end = path.last().unwrap();
+    assert!(end.xz().distance(BEYOND_WALL.xz()) < 0.5);
+
+    assert!(nav_mesh
+        .find_path(BEHIND_WALL, Vec3::new(0., 1., 0.))
+        .is_none());
+}
+
+#[test]
+fn agents_walk_to_their_destination() {
+    temp_save_file("navigation_agent");
+    let mut app = wall_app();
+
+    let mut agent = NavAgent::new(6.);
+    agent.set_destination(Some(BEYOND_WALL));
+    let agent = app
+        .world_mut()
+        .spawn((
+            Transform::from_translation(BEHIND_WALL),
+            RigidBody::Kinematic,
+            agent,
+        ))
+        .id();
+    update_until(&mut app, "the agent to arrive", |world| {
+        let position = world.get::<Position>(agent).unwrap();
+        position.xz().distance(BEYOND_WALL.xz()) < 0.5
+    });
+}
+
+#[test]
+fn agents_stop_when_their_destination_is_cleared() {
+    temp_save_file("navigation_stop");
+    let mut app = wall_app();
+
+    let mut agent = NavAgent::new(6.);
+    agent.set_destination(Some(BEYOND_WALL));
+    let agent = app
+        .world_mut()
+        .spawn((
+            Transform::from_translation(BEHIND_WALL),
+            RigidBody::Kinematic,
+            agent,
+        ))
+        .id();
+    update_until(&mut app, "the agent to set off", |world| {
+        let position = world.get::<Position>(agent).unwrap();
+        position.xz().distance(BEHIND_WALL.xz()) > 1.
+    });
+
+    app.world_mut()
+        .get_mut::<NavAgent>(agent)
+        .unwrap()
+        .set_destination(None);
+    app.update();
+    let stopped = app.world().get::<Position>(agent).unwrap().0;
+    for _ in 0..TICKS_PER_SECOND as usize {
+        app.update();
+    }
+    let velocity = app.world().get::<LinearVelocity>(agent).unwrap();
+    assert_eq!(velocity.xz(), Vec2::ZERO);
+    let position = app.world().get::<Position>(agent).unwrap();
+    assert!(position.xz().distance(stopped.xz()) < 1e-4);
+}
+
+fn wall_app() -> App {
+    let mut app = headless_app_at_level(WALL_LEVEL);
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(
+        1. / TICKS_PER_SECOND,
+    )));
+    start_first_level(&mut app);
+    update_until(&mut app, "the navigation mesh to be baked", |world| {
+        world.contains_resource::<NavMesh>()
+    });
+    app
+}
@@ -0,0 +1,137 @@
+mod common;
+
+use std::time::Duration;
+
+use bevy::{
+    input::{keyboard::Key, mouse::MouseMotion, ButtonState},
+    prelude::*,
+    time::TimeUpdateStrategy,
+};
+use bevy_games_0_15::simple_gun_battle::{EnemyCount, GameState, GunBattleConfig, HealthPoints};
+use common::{
+    headless_app, headless_app_with, in_game_state, press_button, press_key, send_key,
+    send_mouse_button, start_first_level, temp_save_file, update_until,
+};
+
+const TICKS_PER_SECOND: f32 = 64.;
+const PIXELS_TO_ENEMY: f32 = 79.;
+
+#[test]
+fn replaying_a_recording_reproduces_the_run() {
+    temp_save_file("replay");
+    let recording = std::env::temp_dir().join(format!(
+        "simple_gun_battle_replay_{}.ron",
+        std::process::id()
+    ));
+
+    let mut app = headless_app_with(GunBattleConfig {
+        seed: Some(7),
+        record_input: Some(recording.clone()),
+        ..default()
+    });
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(
+        1. / TICKS_PER_SECOND,
+    )));
+    start_first_level(&mut app);
+
+    turn(&mut app, PIXELS_TO_ENEMY);
+    fire(&mut app, 40);
+    turn(&mut app, -2. * PIXELS_TO_ENEMY);
+    fire(&mut app, 12);
+    send_key(
+        &mut app,
+        KeyCode::KeyW,
+        Key::Character("w".into()),
+        ButtonState::Pressed,
+    );
+    ticks(&mut app, 30);
+    app.world_mut().send_event(AppExit::Success);
+    app.update();
+    let recorded = Snapshot::take(app.world_mut());
+    assert!(recorded.health_points.len() > 1);
+
+    let mut app = headless_app_with(GunBattleConfig {
+        replay_input: Some(recording.clone()),
+        ..default()
+    });
+    update_until(&mut app, "the replay to finish", |world| {
+        !world.resource::<Events<AppExit>>().is_empty()
+    });
+    assert_eq!(Snapshot::take(app.world_mut()), recorded);
+    let _ = std::fs::remove_file(recording);
+}
+
+#[test]
+fn game_time_stands_still_in_the_pause_menu() {
+    temp_save_file("replay_pause");
+    let mut app = headless_app();
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(
+        1. / TICKS_PER_SECOND,
+    )));
+    start_first_level(&mut app);
+
+    press_key(&mut app, KeyCode::KeyM, Key::Character("m".into()));
+    update_until(&mut app, "the pause menu", |world| {
+        in_game_state(world, GameState::Menu)
+    });
+    let paused = game_time(app.world());
+    ticks(&mut app, 60);
+    assert_eq!(game_time(app.world()), paused);
+
+    press_button(&mut app, "返回游戏");
+    update_until(&mut app, "the game to resume", |world| {
+        in_game_state(world, GameState::Play)
+    });
+    ticks(&mut app, 2);
+    let (virtual_elapsed, fixed_elapsed) = game_time(app.world());
+    assert!(virtual_elapsed > paused.0 && fixed_elapsed > paused.1);
+}
+
+fn game_time(world: &World) -> (Duration, Duration) {
+    (
+        world.resource::<Time<Virtual>>().elapsed(),
+        world.resource::<Time<Fixed>>().elapsed(),
+    )
+}
+
+#[derive(Debug, PartialEq)]
+struct Snapshot {
+    state: GameState,
+    enemy_count: usize,
+    health_points: Vec<f32>,
+}
+
+impl Snapshot {
+    fn take(world: &mut World) -> Self {
+        let mut health_points: Vec<f32> = world
+            .query::<&HealthPoints>()
+            .iter(world)
+            .map(HealthPoints::current)
+            .collect();
+        health_points.sort_by(f32::total_cmp);
+        Self {
+            state: *world.resource::<State<GameState>>().get(),
+            enemy_count: world.resource::<EnemyCount>().0,
+            health_points,
+        }
+    }
+}
+
+fn turn(app: &mut App, pixels: f32) {
+    app.world_mut().send_event(MouseMotion {
+        delta: Vec2::new(pixels, 0.),
+    });
+    app.update();
+}
+
+fn fire(app: &mut App, count: usize) {
+    send_mouse_button(app, MouseButton::Left, ButtonState::Pressed);
+    ticks(app, count);
+    send_mouse_button(app, MouseButton::Left, ButtonState::Released);
+}
+
+fn ticks(app: &mut App, count: usize) {
+    for _ in 0..count {
+        app.update();
+    }
+}
@@ -0,0 +1,72 @@
+mod common;
+
+use std::{fs, path::PathBuf};
+
+use bevy::prelude::*;
+use bevy_games_0_15::simple_gun_battle::{
+    DisplayMode, FrameLimit, GunBattleConfig, Progress, Settings,
+};
+use common::{headless_app, headless_app_with, temp_save_file};
+
+#[test]
+fn settings_and_progress_survive_a_restart() {
+    let dir = std::env::temp_dir().join(format!("simple_gun_battle_save_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    let path = dir.join("config").join("save.ron");
+    let backup = PathBuf::from(format!("{}.bak", path.display()));
+    let headless_app = || {
+        headless_app_with(GunBattleConfig {
+            save_file: Some(path.clone()),
+            ..default()
+        })
+    };
+
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(&path, "(settings: (mouse_sensitivity: ").unwrap();
+    let mut app = headless_app();
+    app.update();
+    assert_eq!(*app.world().resource::<Settings>(), Settings::default());
+    assert_eq!(*app.world().resource::<Progress>(), Progress::default());
+    assert_eq!(
+        fs::read_to_string(&backup).unwrap(),
+        "(settings: (mouse_sensitivity: "
+    );
+
+    let expected = Settings {
+        mouse_sensitivity: 2.5,
+        invert_y: true,
+        field_of_view: 70.,
+        display_mode: DisplayMode::Windowed,
+        frame_limit: FrameLimit::Manual(144),
+        ..default()
+    };
+    *app.world_mut().resource_mut::<Settings>() = expected.clone();
+    app.world_mut().resource_mut::<Progress>().unlocked_level = 1;
+    app.update();
+    drop(app);
+
+    let app = headless_app();
+    assert_eq!(*app.world().resource::<Settings>(), expected);
+    assert_eq!(app.world().resource::<Progress>().unlocked_level, 1);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn out_of_range_settings_snap_to_the_offered_values_on_load() {
+    let path = temp_save_file("save_file_clamped");
+    fs::write(
+        &path,
+        "(settings: (max_view_pitch: 120.0, field_of_view: 5.0, master_volume: 2.0, \
+         frame_limit: Manual(100)))",
+    )
+    .unwrap();
+
+    let app = headless_app();
+    let settings = app.world().resource::<Settings>();
+    assert_eq!(settings.max_view_pitch, 89.);
+    assert_eq!(settings.field_of_view, 30.);
+    assert_eq!(settings.master_volume, 1.);
+    assert_eq!(settings.frame_limit, FrameLimit::Manual(120));
+    fs::remove_file(&path).unwrap();
+}
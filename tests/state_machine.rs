@@ -0,0 +1,193 @@
+mod common;
+
+use bevy::{asset::LoadState, input::keyboard::Key, prelude::*};
+use bevy_games_0_15::simple_gun_battle::{
+    AppState, DamageEvent, DamageKind, Enemy, EnemyCount, GameScene, GameState, Player, Progress,
+    SceneIndex,
+};
+use common::{
+    app_state, find_button, game_state, headless_app, in_game_state, press_button, press_key,
+    temp_save_file, update_until,
+};
+
+#[test]
+fn state_machine_round_trip() {
+    temp_save_file("state_machine");
+    let mut app = headless_app();
+
+    update_until(&mut app, "the level manifest to load", |world| {
+        let asset_server = world.resource::<AssetServer>();
+        asset_server
+            .get_path_id("campaign.levels.ron")
+            .is_some_and(|id| matches!(asset_server.load_state(id), LoadState::Loaded))
+    });
+    assert_eq!(app_state(&app), Some(AppState::StartMenu));
+    assert!(app.world().get_resource::<SceneIndex>().is_none());
+    assert!(app.world().get_resource::<EnemyCount>().is_none());
+
+    press_button(&mut app, "开始游戏");
+    update_until(&mut app, "the first level to spawn", |world| {
+        world.get_resource::<State<GameState>>().is_some()
+    });
+    assert_eq!(app_state(&app), Some(AppState::Game));
+    assert_eq!(game_state(&app), Some(GameState::Spawn));
+    assert_eq!(scene_index(&app), Some(0));
+    assert_eq!(enemy_count(&app), Some(0));
+    assert_state_scoped_cleaned_up(app.world_mut());
+
+    update_until(&mut app, "the first level to play", |world| {
+        in_game_state(world, GameState::Play) && world.resource::<EnemyCount>().0 == 2
+    });
+    assert_state_scoped_cleaned_up(app.world_mut());
+
+    press_key(&mut app, KeyCode::KeyM, Key::Character("m".into()));
+    update_until(&mut app, "the pause menu", |world| {
+        in_game_state(world, GameState::Menu)
+    });
+    assert!(find_button(app.world_mut(), "返回游戏").is_some());
+    assert_state_scoped_cleaned_up(app.world_mut());
+
+    press_button(&mut app, "返回游戏");
+    update_until(&mut app, "the level to resume", |world| {
+        in_game_state(world, GameState::Play)
+    });
+    assert!(find_button(app.world_mut(), "返回游戏").is_none());
+    assert_state_scoped_cleaned_up(app.world_mut());
+
+    kill_all::<Enemy>(&mut app);
+    update_until(&mut app, "the level to be passed", |world| {
+        in_game_state(world, GameState::NextLevel)
+    });
+    assert_eq!(enemy_count(&app), Some(0));
+    assert_eq!(app.world().resource::<Progress>().unlocked_level, 1);
+    assert_state_scoped_cleaned_up(app.world_mut());
+
+    let despawned = level_entities(app.world_mut());
+    press_button(&mut app, "下一关");
+    update_until(&mut app, "the second level to spawn", |world| {
+        in_game_state(world, GameState::Spawn)
+    });
+    assert_despawned(app.world(), &despawned);
+    assert_no_orphans(app.world_mut());
+    assert_eq!(scene_index(&app), Some(1));
+    assert_state_scoped_cleaned_up(app.world_mut());
+
+    update_until(&mut app, "the second level to play", |world| {
+        in_game_state(world, GameState::Play) && world.resource::<EnemyCount>().0 == 1
+    });
+
+    kill_all::<Player>(&mut app);
+    update_until(&mut app, "the game to be over", |world| {
+        in_game_state(world, GameState::GameOver)
+    });
+    assert_state_scoped_cleaned_up(app.world_mut());
+
+    let despawned = level_entities(app.world_mut());
+    press_button(&mut app, "开始菜单");
+    update_until(&mut app, "the start menu", |world| {
+        world.resource::<State<AppState>>().get() == &AppState::StartMenu
+    });
+    assert_eq!(game_state(&app), None);
+    assert!(app.world().get_resource::<SceneIndex>().is_none());
+    assert!(app.world().get_resource::<EnemyCount>().is_none());
+    assert_despawned(app.world(), &despawned);
+    assert_no_orphans(app.world_mut());
+    assert_state_scoped_cleaned_up(app.world_mut());
+    assert!(find_button(app.world_mut(), "开始游戏").is_some());
+}
+
+fn scene_index(app: &App) -> Option<usize> {
+    app.world()
+        .get_resource::<SceneIndex>()
+        .map(|scene_index| scene_index.0)
+}
+
+fn enemy_count(app: &App) -> Option<usize> {
+    app.world()
+        .get_resource::<EnemyCount>()
+        .map(|enemy_count| enemy_count.0)
+}
+
+fn kill_all<T: Component>(app: &mut App) {
+    let world = app.world_mut();
+    let targets: Vec<Entity> = world
+        .query_filtered::<Entity, With<T>>()
+        .iter(world)
+        .collect();
+    assert!(!targets.is_empty(), "there is nothing to kill");
+    for target in targets {
+        world.trigger(DamageEvent {
+            target,
+            source: None,
+            amount: 1000.,
+            kind: DamageKind::OutOfWorld,
+            hit_point: None,
+        });
+    }
+    world.flush();
+}
+
+fn level_entities(world: &mut World) -> Vec<Entity> {
+    let roots: Vec<Entity> = world
+        .query_filtered::<Entity, Or<(With<GameScene>, With<Player>)>>()
+        .iter(world)
+        .collect();
+    assert!(!roots.is_empty(), "there is no level to despawn");
+    let mut children = world.query::<&Children>();
+    let mut entities = Vec::new();
+    let mut pending = roots;
+    while let Some(entity) = pending.pop() {
+        entities.push(entity);
+        if let Ok(entity_children) = children.get(world, entity) {
+            pending.extend(entity_children.iter());
+        }
+    }
+    entities
+}
+
+fn assert_despawned(world: &World, entities: &[Entity]) {
+    for entity in entities {
+        assert!(
+            !world.entities().contains(*entity),
+            "{entity} outlived its level"
+        );
+    }
+}
+
+fn assert_no_orphans(world: &mut World) {
+    let mut parents = world.query::<(Entity, &Parent)>();
+    for (entity, parent) in parents.iter(world) {
+        assert!(
+            world.entities().contains(parent.get()),
+            "{entity} points at the despawned parent {}",
+            parent.get()
+        );
+    }
+}
+
+fn assert_state_scoped_cleaned_up(world: &mut World) {
+    let app_state = world
+        .get_resource::<State<AppState>>()
+        .map(|state| *state.get());
+    let game_state = world
+        .get_resource::<State<GameState>>()
+        .map(|state| *state.get());
+    let mut app_scoped = world.query::<(Entity, &StateScoped<AppState>)>();
+    for (entity, scoped) in app_scoped.iter(world) {
+        assert_eq!(
+            Some(scoped.0),
+            app_state,
+            "{entity} is scoped to {:?}",
+            scoped.0
+        );
+    }
+    let mut game_scoped = world.query::<(Entity, &StateScoped<GameState>)>();
+    for (entity, scoped) in game_scoped.iter(world) {
+        assert_eq!(
+            Some(scoped.0),
+            game_state,
+            "{entity} is scoped to {:?}",
+            scoped.0
+        );
+    }
+}
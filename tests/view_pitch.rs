@@ -0,0 +1,45 @@
+mod common;
+
+use bevy::{input::mouse::MouseMotion, prelude::*};
+use bevy_games_0_15::simple_gun_battle::{PlayerView, Settings};
+use common::{headless_app, player_transform, start_first_level, temp_save_file};
+
+#[test]
+fn mouse_look_yaws_the_body_and_clamps_the_view_pitch() {
+    temp_save_file("view_pitch");
+    let mut app = headless_app();
+    start_first_level(&mut app);
+
+    let start = player_transform(app.world_mut()).rotation;
+    app.world_mut().send_event(MouseMotion {
+        delta: Vec2::new(200., 0.),
+    });
+    app.update();
+    let yawed = player_transform(app.world_mut()).rotation;
+    assert!(yawed.angle_between(start) > 0.1);
+    let (axis, _) = (yawed * start.inverse()).to_axis_angle();
+    assert!(axis.abs_diff_eq(Vec3::Y, 1e-3) || axis.abs_diff_eq(Vec3::NEG_Y, 1e-3));
+
+    for _ in 0..10 {
+        app.world_mut().send_event(MouseMotion {
+            delta: Vec2::new(0., 1000.),
+        });
+        app.update();
+    }
+    let (pitch, max_pitch) = view_pitch(app.world_mut());
+    let configured = app.world().resource::<Settings>().max_view_pitch;
+    assert!((max_pitch - configured.to_radians()).abs() < 1e-4);
+    assert!((pitch - max_pitch).abs() < 1e-4);
+
+    app.world_mut().send_event(MouseMotion {
+        delta: Vec2::new(0., -100.),
+    });
+    app.update();
+    let (pitch, max_pitch) = view_pitch(app.world_mut());
+    assert!(pitch < max_pitch - 0.1);
+}
+
+fn view_pitch(world: &mut World) -> (f32, f32) {
+    let view = world.query::<&PlayerView>().single(world);
+    (view.pitch, view.max_pitch)
+}
@@ -0,0 +1,106 @@
+mod common;
+
+use bevy::{input::keyboard::Key, prelude::*};
+use bevy_games_0_15::simple_gun_battle::{
+    DropdownChanged, Focused, Slider, SliderChanged, TextInputChanged, TextInputSubmitted,
+    ToggleChanged, Widgets,
+};
+use common::{headless_app, press_key, temp_save_file, update_until};
+
+#[derive(Component)]
+struct TestWidget;
+
+#[derive(Resource, Default)]
+struct Received(Vec<String>);
+
+#[test]
+fn widgets_emit_typed_change_events() {
+    temp_save_file("widgets");
+    let mut app = headless_app();
+    update_until(&mut app, "the start menu to take focus", |world| {
+        world.query::<&Focused>().iter(world).next().is_some()
+    });
+    app.init_resource::<Received>()
+        .add_observer(
+            |trigger: Trigger<SliderChanged>, mut received: ResMut<Received>| {
+                received.0.push(format!("slider {:.1}", trigger.event().0));
+            },
+        )
+        .add_observer(
+            |trigger: Trigger<ToggleChanged>, mut received: ResMut<Received>| {
+                received.0.push(format!("toggle {}", trigger.event().0));
+            },
+        )
+        .add_observer(
+            |trigger: Trigger<DropdownChanged>, mut received: ResMut<Received>| {
+                received.0.push(format!("dropdown {}", trigger.event().0));
+            },
+        )
+        .add_observer(
+            |trigger: Trigger<TextInputChanged>, mut received: ResMut<Received>| {
+                received.0.push(format!("changed {}", trigger.event().0));
+            },
+        )
+        .add_observer(
+            |trigger: Trigger<TextInputSubmitted>, mut received: ResMut<Received>| {
+                received.0.push(format!("submitted {}", trigger.event().0));
+            },
+        );
+
+    let world = app.world_mut();
+    let mut commands = world.commands();
+    let slider = commands
+        .slider(Slider::new(0.5, (0., 1.), 0.1), TestWidget)
+        .id();
+    let toggle = commands.toggle(false, TestWidget).id();
+    let dropdown = commands.dropdown(["甲", "乙", "丙"], 2, TestWidget).id();
+    let text_input = commands.text_input("", 2, TestWidget).id();
+    world.flush();
+
+    focus(&mut app, slider);
+    press_key(&mut app, KeyCode::ArrowRight, Key::ArrowRight);
+    press_key(&mut app, KeyCode::ArrowLeft, Key::ArrowLeft);
+    press_key(&mut app, KeyCode::ArrowLeft, Key::ArrowLeft);
+
+    for widget in [toggle, dropdown] {
+        app.world_mut()
+            .entity_mut(widget)
+            .insert(Interaction::Pressed);
+        app.update();
+    }
+
+    focus(&mut app, text_input);
+    for character in ["a", "b", "c"] {
+        press_key(&mut app, KeyCode::KeyA, Key::Character(character.into()));
+    }
+    press_key(&mut app, KeyCode::Backspace, Key::Backspace);
+    press_key(&mut app, KeyCode::Enter, Key::Enter);
+
+    assert_eq!(
+        app.world().resource::<Received>().0,
+        [
+            "slider 0.6",
+            "slider 0.5",
+            "slider 0.4",
+            "toggle true",
+            "dropdown 0",
+            "changed a",
+            "changed ab",
+            "changed a",
+            "submitted a",
+        ]
+    );
+}
+
+fn focus(app: &mut App, entity: Entity) {
+    let world = app.world_mut();
+    let focused: Vec<Entity> = world
+        .query_filtered::<Entity, With<Focused>>()
+        .iter(world)
+        .collect();
+    for focused in focused {
+        world.entity_mut(focused).remove::<Focused>();
+    }
+    world.entity_mut(entity).insert(Focused);
+    app.update();
+}